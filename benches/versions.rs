@@ -0,0 +1,90 @@
+use check_latest::test_util::serve;
+use check_latest::{Channel, FetchConfig, Versions};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+/// How many versions are in the fixture.
+const FIXTURE_LEN: usize = 1000;
+
+/// Builds a response body with `FIXTURE_LEN` versions, like a crate with a
+/// long release history. Every 10th version is yanked, and every 25th version
+/// is a pre-release.
+fn fixture() -> String {
+    let versions: Vec<String> = (0..FIXTURE_LEN)
+        .map(|i| {
+            let num = if i % 25 == 0 {
+                format!("{}.{}.0-rc.1", i / 100, i % 100)
+            } else {
+                format!("{}.{}.{}", i / 100, (i / 10) % 10, i % 10)
+            };
+            format!(
+                r#"{{"num": "{}", "yanked": {}, "created_at": "2020-01-01T00:00:00Z", "downloads": {}}}"#,
+                num,
+                i % 10 == 0,
+                i * 100,
+            )
+        })
+        .collect();
+    format!(r#"{{"versions": [{}]}}"#, versions.join(","))
+}
+
+fn parse(c: &mut Criterion) {
+    let body = fixture();
+    c.bench_function("from_json", |b| {
+        b.iter(|| Versions::from_json(black_box(&body)).unwrap())
+    });
+}
+
+fn select(c: &mut Criterion) {
+    let versions = Versions::from_json(&fixture()).unwrap();
+    c.bench_function("max_version", |b| {
+        b.iter(|| black_box(&versions).max_version())
+    });
+    // The result is remembered after the first call, so each iteration needs
+    // a fresh list to measure the selection instead of the cache.
+    c.bench_function("max_unyanked_version", |b| {
+        b.iter_batched_ref(
+            || Versions::from(versions.versions().to_vec()),
+            |versions| {
+                black_box(versions.max_unyanked_version());
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("max_stable_version", |b| {
+        b.iter(|| black_box(&versions).max_stable_version())
+    });
+    c.bench_function("max_for", |b| {
+        b.iter(|| black_box(&versions).max_for(Some(5), Channel::Named(String::from("rc"))))
+    });
+}
+
+fn compare(c: &mut Criterion) {
+    let versions = Versions::from_json(&fixture()).unwrap();
+    c.bench_function("partial_cmp each", |b| {
+        b.iter(|| {
+            black_box(&versions)
+                .versions()
+                .iter()
+                .map(|v| v.partial_cmp(black_box(&"5.0.0")))
+                .collect::<Vec<_>>()
+        })
+    });
+    c.bench_function("compare_all_against", |b| {
+        b.iter(|| {
+            black_box(&versions)
+                .compare_all_against(black_box("5.0.0"))
+                .unwrap()
+        })
+    });
+}
+
+fn fetch(c: &mut Criterion) {
+    let (base_url, _server) = serve(&Versions::from_json(&fixture()).unwrap());
+    let config = FetchConfig::new("check-latest-bench/1.0.0").base_url(&base_url);
+    c.bench_function("fetch", |b| {
+        b.iter(|| Versions::fetch("my-cool-crate", &config).unwrap())
+    });
+}
+
+criterion_group!(benches, parse, select, compare, fetch);
+criterion_main!(benches);
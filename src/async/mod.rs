@@ -10,12 +10,25 @@
 //! # }
 //! ```
 
-use crate::{build_url, Versions};
-use anyhow::{Context, Result};
+use crate::__private::CheckOptions;
+use crate::config::Auth;
+use crate::{build_url, check_response_size, redirect_policy, FetchConfig, Version, Versions};
+use anyhow::{anyhow, Context, Result};
+use futures_util::io::{AsyncRead, AsyncReadExt};
+use futures_util::stream::{self, StreamExt};
+use reqwest::header::USER_AGENT;
+use std::collections::HashMap;
+use std::future::Future;
 
 /// Checks if there is a version available that is greater than the current
 /// version.
 ///
+/// # Options
+///
+/// - `include_prerelease = bool`: if pre-releases should be considered. The
+///   default is `false`, like Cargo, which doesn't choose a pre-release
+///   unless it is asked for.
+///
 /// # Returns
 ///
 /// Assume the current version is `a.b.c`, and we are looking at versions that
@@ -39,14 +52,16 @@ use anyhow::{Context, Result};
 #[macro_export]
 macro_rules! check_max_async {
     () => {
+        $crate::check_max_async!(include_prerelease = false)
+    };
+    (include_prerelease = $include_prerelease:expr $(,)?) => {
         async {
-            $crate::new_versions_async!().await.map(|versions| {
-                let max = versions.max_unyanked_version()?.clone();
-                if max > $crate::crate_version!() {
-                    Some(max)
-                } else {
-                    None
-                }
+            $crate::new_versions_async!().await.and_then(|versions| {
+                $crate::__private::max_update(
+                    &versions,
+                    $crate::crate_version!(),
+                    $include_prerelease,
+                )
             })
         }
     };
@@ -54,6 +69,12 @@ macro_rules! check_max_async {
 /// Checks if there is a higher minor version available with the same major
 /// version
 ///
+/// # Options
+///
+/// - `include_prerelease = bool`: if pre-releases should be considered. The
+///   default is `false`, like Cargo, which doesn't choose a pre-release
+///   unless it is asked for.
+///
 /// # Returns
 ///
 /// Assume the current version is `a.b.c`, and we are looking at versions that
@@ -77,13 +98,16 @@ macro_rules! check_max_async {
 #[macro_export]
 macro_rules! check_minor_async {
     () => {
+        $crate::check_minor_async!(include_prerelease = false)
+    };
+    (include_prerelease = $include_prerelease:expr $(,)?) => {
         async {
             $crate::new_versions_async!().await.and_then(|versions| {
-                let major_version = $crate::crate_major_version!().parse()?;
-                let max = versions.max_unyanked_minor_version(major_version);
-                let max = max.cloned();
-                let max = max.filter(|max| max > $crate::crate_version!());
-                Ok(max)
+                $crate::__private::minor_update(
+                    &versions,
+                    $crate::crate_version!(),
+                    $include_prerelease,
+                )
             })
         }
     };
@@ -92,6 +116,12 @@ macro_rules! check_minor_async {
 /// Checks if there is a higher patch available, within the same major.minor
 /// version.
 ///
+/// # Options
+///
+/// - `include_prerelease = bool`: if pre-releases should be considered. The
+///   default is `false`, like Cargo, which doesn't choose a pre-release
+///   unless it is asked for.
+///
 /// # Returns
 ///
 /// Assume the current version is `a.b.c`, and we are looking at versions that
@@ -115,19 +145,87 @@ macro_rules! check_minor_async {
 #[macro_export]
 macro_rules! check_patch_async {
     () => {
+        $crate::check_patch_async!(include_prerelease = false)
+    };
+    (include_prerelease = $include_prerelease:expr $(,)?) => {
         async {
             $crate::new_versions_async!().await.and_then(|versions| {
-                let major_version = $crate::crate_major_version!().parse()?;
-                let minor_version = $crate::crate_minor_version!().parse()?;
-                let max = versions.max_unyanked_patch(major_version, minor_version);
-                let max = max.cloned();
-                let max = max.filter(|max| max > $crate::crate_version!());
-                Ok(max)
+                $crate::__private::patch_update(
+                    &versions,
+                    $crate::crate_version!(),
+                    $include_prerelease,
+                )
             })
         }
     };
 }
 
+/// Checks if there is an update available, with options.
+///
+/// This is the async equivalent of `check!`, and takes the same options.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn run() {
+/// use check_latest::{check_async, Channel};
+///
+/// if let Ok(Some(version)) = check_async!(channel = Channel::Stable, req = "^1").await {
+///     println!("A new version is available: {}", version);
+/// }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! check_async {
+    ($($option:ident = $value:expr),* $(,)?) => {
+        async {
+            let mut options = $crate::__private::CheckOptions::new(
+                $crate::crate_name!(),
+                $crate::crate_version!(),
+                $crate::user_agent!(),
+            );
+            $(options.$option($value);)*
+            options.async_check().await
+        }
+    };
+}
+
+/// Defines a `check_self_async` function in *your* crate.
+///
+/// The generated function is the function-call equivalent of
+/// `check_max_async!()`: it checks if there is a version of *your* crate
+/// available that is greater than the version that is running. Because it is
+/// generated inside your crate, it picks up the name and version from *your*
+/// `Cargo.toml`.
+///
+/// The function is private by default, but a visibility can be passed to the
+/// macro.
+///
+/// ```text
+/// async fn check_self_async() -> Result<Option<Version>>
+/// ```
+///
+/// # Example
+///
+/// ```rust,no_run
+/// check_latest::define_check_self_async!(pub(crate));
+///
+/// # async fn run() {
+/// if let Ok(Some(version)) = check_self_async().await {
+///     println!("A new version is available: {}", version);
+/// }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! define_check_self_async {
+    ($vis:vis) => {
+        /// Checks if there is a newer version of this crate available.
+        $vis async fn check_self_async() -> $crate::__private::Result<Option<$crate::Version>> {
+            $crate::check_max_async!().await
+        }
+    };
+}
+
 impl Versions {
     /// - `crate_name`: The crate that the version should be checked for.
     /// - `user_agent`: without a proper User-Agent, the request to the
@@ -152,6 +250,11 @@ impl Versions {
     /// If you believe you've received this message in error, please email help@crates.io and include the request id {}.
     /// ```
     ///
+    /// # Redirects
+    ///
+    /// Up to 5 redirects will be followed, which lets a renamed crate resolve
+    /// to its new location. Redirects to a different origin (scheme, host, or
+    /// port) are refused and result in an error.
     ///
     /// # Example
     ///
@@ -167,20 +270,316 @@ impl Versions {
     ///
     /// [Crates.io]: https://crates.io/
     pub async fn async_new(crate_name: &str, user_agent: &str) -> Result<Versions> {
-        let url = build_url(crate_name);
-        let response: Versions = reqwest::Client::builder()
-            .user_agent(user_agent)
-            .build()
-            .context("Couldn't build client")?
-            .get(&url)
-            .send()
+        Versions::async_fetch(crate_name, &FetchConfig::new(user_agent)).await
+    }
+    /// Like `async_new`, but with more control over how the versions are
+    /// fetched.
+    ///
+    /// Any credentials in `config` are attached to the request, which allows
+    /// checking versions on a private registry. Because redirects to another
+    /// origin are refused, credentials are never sent to a different host.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// use check_latest::{FetchConfig, Versions};
+    ///
+    /// let config = FetchConfig::new("my-awesome-crate-bin/1.0.0")
+    ///     .basic_auth("my-username", Some("my-password"));
+    /// if let Ok(versions) = Versions::async_fetch("my-awesome-crate-bin", &config).await {
+    ///     /* Do your stuff */
+    /// }
+    /// # }
+    /// ```
+    pub async fn async_fetch(crate_name: &str, config: &FetchConfig) -> Result<Versions> {
+        let client = build_client(config)?;
+        Versions::async_fetch_with_client(&client, crate_name, config).await
+    }
+    /// Fetches the versions of `crate_name` again, reusing `client` for the
+    /// request.
+    ///
+    /// Reusing a client avoids building a new one, and lets its connection
+    /// pool be reused, every time the versions are refreshed. This is useful
+    /// for a long-running process that polls for new versions.
+    ///
+    /// If the versions can't be fetched, `self` is left unchanged.
+    ///
+    /// *__NOTE__ `client` is used as-is, so it won't have the redirect policy
+    /// described in `async_new`.*
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// use check_latest::{FetchConfig, Versions};
+    ///
+    /// let client = reqwest::Client::new();
+    /// let config = FetchConfig::new("my-awesome-crate-bin/1.0.0");
+    /// let mut versions = Versions::async_fetch("my-awesome-crate-bin", &config).await.unwrap();
+    /// loop {
+    ///     /* Wait a while */
+    ///     if versions.async_refresh_with(&client, "my-awesome-crate-bin", &config).await.is_ok() {
+    ///         /* Do your stuff */
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn async_refresh_with(
+        &mut self,
+        client: &reqwest::Client,
+        crate_name: &str,
+        config: &FetchConfig,
+    ) -> Result<()> {
+        *self = Versions::async_fetch_with_client(client, crate_name, config).await?;
+        Ok(())
+    }
+    /// Like `async_new`, but blocks the current thread until the versions are
+    /// fetched, driving the request on the runtime that `handle` belongs to.
+    ///
+    /// This is useful for synchronous code that already has a Tokio runtime,
+    /// like a thread that was given a `Handle`, so that a second runtime
+    /// doesn't need to be started.
+    ///
+    /// # Panics
+    ///
+    /// Like [`Handle::block_on`], this panics if it is called from within an
+    /// asynchronous context, like inside an `async fn` that is being run by a
+    /// runtime, which fails with "Cannot start a runtime from within a
+    /// runtime". In async code, `.await` `async_new` instead, or call this
+    /// from [`spawn_blocking`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let runtime = tokio::runtime::Runtime::new().unwrap();
+    /// let handle = runtime.handle().clone();
+    /// std::thread::spawn(move || {
+    ///     let versions = Versions::new_on_handle(&handle, "my-cool-crate", "my-cool-crate/1.0.0");
+    /// })
+    /// .join()
+    /// .unwrap();
+    /// ```
+    ///
+    /// [`Handle::block_on`]: tokio::runtime::Handle::block_on
+    /// [`spawn_blocking`]: tokio::task::spawn_blocking
+    pub fn new_on_handle(
+        handle: &tokio::runtime::Handle,
+        crate_name: &str,
+        user_agent: &str,
+    ) -> Result<Versions> {
+        fetch_on_handle(handle, crate_name, &FetchConfig::new(user_agent))
+    }
+    /// Like `Versions::from_reader`, but reads the body asynchronously.
+    ///
+    /// The body is read completely before it is parsed. A Tokio reader can be
+    /// used with the [`compat`] adapter from `tokio-util`.
+    ///
+    /// Like `from_reader`, the size of the body is unbounded, because
+    /// `FetchConfig::max_response_size` only applies to fetched responses. If
+    /// `reader` isn't trusted, limit it with [`AsyncReadExt::take`] first.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// use check_latest::Versions;
+    ///
+    /// let body: &[u8] = br#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+    /// let versions = Versions::async_from_reader(body).await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`compat`]: https://docs.rs/tokio-util/latest/tokio_util/compat/index.html
+    /// [`AsyncReadExt::take`]: futures_util::io::AsyncReadExt::take
+    pub async fn async_from_reader<R: AsyncRead + Unpin>(mut reader: R) -> Result<Versions> {
+        let mut body = Vec::new();
+        reader
+            .read_to_end(&mut body)
             .await
-            .context("Couldn't request crate info")?
-            .json()
+            .context("Couldn't read body")?;
+        serde_json::from_slice(&body).context("Couldn't read as JSON")
+    }
+    async fn async_fetch_with_client(
+        client: &reqwest::Client,
+        crate_name: &str,
+        config: &FetchConfig,
+    ) -> Result<Versions> {
+        let url = build_url(&config.resolved_api_root(), crate_name);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(url = %url, "Requesting crate versions");
+        let request = client.get(&url).header(USER_AGENT, config.user_agent()?);
+        let request = match &config.auth {
+            None => request,
+            Some(Auth::Basic { username, password }) => {
+                request.basic_auth(username, password.as_ref())
+            }
+            Some(Auth::Bearer(token)) => request.bearer_auth(token),
+        };
+        let mut response = request
+            .send()
             .await
-            .context("Couldn't read as JSON")?;
-        Ok(response)
+            .context("Couldn't request crate info")?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            url = %url,
+            status = %response.status(),
+            content_length = ?response.content_length(),
+            "Received response",
+        );
+        let limit = config.response_size_limit();
+        if let Some(length) = response.content_length() {
+            check_response_size(usize::try_from(length).unwrap_or(usize::MAX), limit)?;
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.context("Couldn't read response")? {
+            check_response_size(body.len() + chunk.len(), limit)?;
+            body.extend_from_slice(&chunk);
+        }
+        let body = String::from_utf8(body).context("Couldn't read response")?;
+        Versions::from_fetched(url, &body, config)
+    }
+}
+
+/// Blocks on `Versions::async_fetch` with `handle`, for
+/// `Versions::new_on_handle`.
+fn fetch_on_handle(
+    handle: &tokio::runtime::Handle,
+    crate_name: &str,
+    config: &FetchConfig,
+) -> Result<Versions> {
+    handle.block_on(Versions::async_fetch(crate_name, config))
+}
+
+/// Builds the client that `Versions::async_fetch` uses, with the options in
+/// `config`.
+fn build_client(config: &FetchConfig) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().redirect(redirect_policy());
+    if let Some(timeout) = config.request_timeout() {
+        builder = builder.timeout(timeout);
+    }
+    for cert in config.root_certificates() {
+        builder = builder.add_root_certificate(cert.clone());
     }
+    builder.build().context("Couldn't build client")
+}
+
+impl CheckOptions {
+    /// Fetches the versions and chooses the update. Used by `check_async!`.
+    pub async fn async_check(&self) -> Result<Option<Version>> {
+        let versions = Versions::async_fetch(self.crate_name, &self.config).await?;
+        self.select(&versions)
+    }
+}
+
+/// Fetches the versions of many crates, with at most `concurrency` requests
+/// in flight at once.
+///
+/// Limiting the concurrency avoids opening a connection for every crate at
+/// once, which could overwhelm [Crates.io] and trip its rate limits. A
+/// `concurrency` of `0` is treated as `1`.
+///
+/// After each crate is checked, `progress` is called with the number of
+/// crates that have been checked so far and the total number of crates, which
+/// can be used to update a progress bar.
+///
+/// The results are keyed by crate name. All of the requests share one client,
+/// so connections are reused.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn run() {
+/// use check_latest::r#async::check_many_async;
+///
+/// let crate_names = ["serde", "tokio", "anyhow"];
+/// let results = check_many_async(&crate_names, "my-cool-crate/1.0.0", 4, |done, total| {
+///     println!("Checked {}/{}", done, total);
+/// })
+/// .await;
+/// for (crate_name, versions) in results {
+///     if let Ok(versions) = versions {
+///         println!("{}: {:?}", crate_name, versions.max_stable_version());
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [Crates.io]: https://crates.io/
+pub async fn check_many_async<I, S, F>(
+    crate_names: I,
+    user_agent: &str,
+    concurrency: usize,
+    progress: F,
+) -> HashMap<String, Result<Versions>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+    F: FnMut(usize, usize),
+{
+    let crate_names = crate_names
+        .into_iter()
+        .map(|crate_name| String::from(crate_name.as_ref()))
+        .collect();
+    let config = FetchConfig::new(user_agent);
+    fetch_many(crate_names, &config, concurrency, progress).await
+}
+
+/// Fetches the versions of each crate with one shared client, for
+/// `check_many_async`.
+async fn fetch_many<F>(
+    crate_names: Vec<String>,
+    config: &FetchConfig,
+    concurrency: usize,
+    progress: F,
+) -> HashMap<String, Result<Versions>>
+where
+    F: FnMut(usize, usize),
+{
+    let client = &build_client(config);
+    run_many(
+        crate_names,
+        concurrency,
+        progress,
+        |crate_name| async move {
+            let versions = match client {
+                Ok(client) => Versions::async_fetch_with_client(client, &crate_name, config).await,
+                Err(e) => Err(anyhow!("{:#}", e)),
+            };
+            (crate_name, versions)
+        },
+    )
+    .await
+}
+
+/// Runs `fetch` for each crate, with at most `concurrency` running at once,
+/// and calls `progress` as each one finishes.
+async fn run_many<F, Fetch, Fut>(
+    crate_names: Vec<String>,
+    concurrency: usize,
+    mut progress: F,
+    fetch: Fetch,
+) -> HashMap<String, Result<Versions>>
+where
+    F: FnMut(usize, usize),
+    Fetch: Fn(String) -> Fut,
+    Fut: Future<Output = (String, Result<Versions>)>,
+{
+    let total = crate_names.len();
+    let mut results = stream::iter(crate_names)
+        .map(fetch)
+        .buffer_unordered(concurrency.max(1));
+
+    let mut checked = HashMap::with_capacity(total);
+    let mut done = 0;
+    while let Some((crate_name, versions)) = results.next().await {
+        checked.insert(crate_name, versions);
+        done += 1;
+        progress(done, total);
+    }
+    checked
 }
 
 /// Helper for creating a new `Versions`.
@@ -237,3 +636,140 @@ macro_rules! new_versions_async {
         )
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Runs `run_many` with a fetch that only tracks how many are running at
+    /// once, and returns the most that were.
+    fn max_in_flight(crate_count: usize, concurrency: usize) -> usize {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let in_flight = AtomicUsize::new(0);
+        let max = AtomicUsize::new(0);
+        let crate_names = (0..crate_count).map(|i| format!("crate-{}", i)).collect();
+        let checked = runtime.block_on(run_many(
+            crate_names,
+            concurrency,
+            |_, _| {},
+            |name| {
+                let (in_flight, max) = (&in_flight, &max);
+                async move {
+                    let running = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max.fetch_max(running, Ordering::SeqCst);
+                    tokio::task::yield_now().await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    (name, Ok(Versions::from(Vec::new())))
+                }
+            },
+        ));
+        assert_eq!(checked.len(), crate_count);
+        max.into_inner()
+    }
+
+    #[tokio::test]
+    async fn async_from_reader() {
+        let body = r#"{"versions": [
+            {"num": "1.0.0", "yanked": false, "created_at": "2020-01-01T00:00:00Z"},
+            {"num": "1.1.0", "yanked": true, "created_at": null}
+        ]}"#;
+        let expected = Versions::from_reader(body.as_bytes()).unwrap();
+        let versions = Versions::async_from_reader(body.as_bytes()).await.unwrap();
+        assert_eq!(versions.versions(), expected.versions());
+        for (version, expected) in versions.versions().iter().zip(expected.versions()) {
+            assert_eq!(version.yanked, expected.yanked);
+            assert_eq!(version.created_at, expected.created_at);
+        }
+        assert!(Versions::async_from_reader(&b"not json"[..]).await.is_err());
+    }
+
+    #[test]
+    fn run_many_limits_concurrency() {
+        assert_eq!(max_in_flight(10, 3), 3);
+        assert_eq!(max_in_flight(2, 3), 2);
+        assert_eq!(max_in_flight(10, 0), 1);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn fetch_many_with_shared_client() {
+        let body = r#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+        let (url, _server) = crate::test_util::serve(&Versions::from_json(body).unwrap());
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&url);
+        let crate_names = vec![String::from("a"), String::from("b"), String::from("c")];
+        let checked = fetch_many(crate_names, &config, 2, |_, _| {}).await;
+        assert_eq!(checked.len(), 3);
+        for crate_name in ["a", "b", "c"] {
+            let versions = checked[crate_name].as_ref().unwrap();
+            assert_eq!(versions.max_version().unwrap(), &"1.0.0");
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn async_refresh_with() {
+        let body = r#"{"versions": [
+            {"num": "1.0.0", "yanked": false, "created_at": null},
+            {"num": "2.0.0", "yanked": false, "created_at": null}
+        ]}"#;
+        let (url, _server) = crate::test_util::serve(&Versions::from_json(body).unwrap());
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&url);
+        let mut versions = crate::tests::versions(&[("1.0.0", false)]);
+        assert_eq!(versions.max_unyanked_version().unwrap(), &"1.0.0");
+
+        let client = reqwest::Client::new();
+        versions
+            .async_refresh_with(&client, "my-crate", &config)
+            .await
+            .unwrap();
+        assert_eq!(versions.versions().len(), 2);
+        assert_eq!(versions.max_unyanked_version().unwrap(), &"2.0.0");
+        assert!(versions.source_url().unwrap().starts_with(&url));
+
+        let config = FetchConfig::new("my-crate/1.0.0").base_url("http://localhost:1");
+        assert!(versions
+            .async_refresh_with(&client, "my-crate", &config)
+            .await
+            .is_err());
+        assert_eq!(versions.versions().len(), 2);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn fetch_on_handle() {
+        let body = r#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+        let (url, _server) = crate::test_util::serve(&Versions::from_json(body).unwrap());
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&url);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let handle = runtime.handle().clone();
+        let versions =
+            std::thread::spawn(move || super::fetch_on_handle(&handle, "my-crate", &config))
+                .join()
+                .unwrap()
+                .unwrap();
+        assert_eq!(versions.max_version().unwrap(), &"1.0.0");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Cannot start a runtime from within a runtime")]
+    async fn new_on_handle_in_runtime() {
+        let handle = tokio::runtime::Handle::current();
+        let _ = Versions::new_on_handle(&handle, "my-crate", "my-crate/1.0.0");
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn fetch_many_progress() {
+        let body = r#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+        let (url, _server) = crate::test_util::serve(&Versions::from_json(body).unwrap());
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&url);
+        let crate_names = vec![String::from("a"), String::from("b"), String::from("c")];
+        let mut calls = Vec::new();
+        fetch_many(crate_names, &config, 2, |done, total| {
+            calls.push((done, total))
+        })
+        .await;
+        assert_eq!(calls, vec![(1, 3), (2, 3), (3, 3)]);
+    }
+}
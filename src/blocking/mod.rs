@@ -8,12 +8,33 @@
 //! }
 //! ```
 
-use crate::{build_url, Versions};
-use anyhow::{Context, Result};
+use crate::__private::CheckOptions;
+use crate::config::Auth;
+use crate::{build_url, check_response_size, redirect_policy, FetchConfig, Version, Versions};
+use anyhow::{bail, Context, Result};
+use reqwest::header::USER_AGENT;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How often `Versions::fetch_cancellable` checks if it was cancelled while
+/// it waits for the request.
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
 
 /// Checks if there is a version available that is greater than the current
 /// version.
 ///
+/// # Options
+///
+/// - `include_prerelease = bool`: if pre-releases should be considered. The
+///   default is `false`, like Cargo, which doesn't choose a pre-release
+///   unless it is asked for.
+///
 /// # Returns
 ///
 /// Assume the current version is `a.b.c`, and we are looking at versions that
@@ -31,23 +52,30 @@ use anyhow::{Context, Result};
 /// if let Ok(Some(version)) = check_max!() {
 ///     println!("A new version is available: {}", version);
 /// }
+/// if let Ok(Some(version)) = check_max!(include_prerelease = true) {
+///     println!("A new version is available to try: {}", version);
+/// }
 /// ```
 #[macro_export]
 macro_rules! check_max {
     () => {
-        $crate::new_versions!().map(|versions| {
-            let max = versions.max_unyanked_version()?.clone();
-            if max > $crate::crate_version!() {
-                Some(max)
-            } else {
-                None
-            }
+        $crate::check_max!(include_prerelease = false)
+    };
+    (include_prerelease = $include_prerelease:expr $(,)?) => {
+        $crate::new_versions!().and_then(|versions| {
+            $crate::__private::max_update(&versions, $crate::crate_version!(), $include_prerelease)
         })
     };
 }
 /// Checks if there is a higher minor version available with the same major
 /// version.
 ///
+/// # Options
+///
+/// - `include_prerelease = bool`: if pre-releases should be considered. The
+///   default is `false`, like Cargo, which doesn't choose a pre-release
+///   unless it is asked for.
+///
 /// # Returns
 ///
 /// Assume the current version is `a.b.c`, and we are looking at versions that
@@ -69,12 +97,15 @@ macro_rules! check_max {
 #[macro_export]
 macro_rules! check_minor {
     () => {
+        $crate::check_minor!(include_prerelease = false)
+    };
+    (include_prerelease = $include_prerelease:expr $(,)?) => {
         $crate::new_versions!().and_then(|versions| {
-            let major_version = $crate::crate_major_version!().parse()?;
-            let max = versions.max_unyanked_minor_version(major_version);
-            let max = max.cloned();
-            let max = max.filter(|max| max > $crate::crate_version!());
-            Ok(max)
+            $crate::__private::minor_update(
+                &versions,
+                $crate::crate_version!(),
+                $include_prerelease,
+            )
         })
     };
 }
@@ -82,6 +113,12 @@ macro_rules! check_minor {
 /// Checks if there is a higher patch available, within the same major.minor
 /// version.
 ///
+/// # Options
+///
+/// - `include_prerelease = bool`: if pre-releases should be considered. The
+///   default is `false`, like Cargo, which doesn't choose a pre-release
+///   unless it is asked for.
+///
 /// # Returns
 ///
 /// Assume the current version is `a.b.c`, and we are looking at versions that
@@ -103,17 +140,101 @@ macro_rules! check_minor {
 #[macro_export]
 macro_rules! check_patch {
     () => {
+        $crate::check_patch!(include_prerelease = false)
+    };
+    (include_prerelease = $include_prerelease:expr $(,)?) => {
         $crate::new_versions!().and_then(|versions| {
-            let major_version = $crate::crate_major_version!().parse()?;
-            let minor_version = $crate::crate_minor_version!().parse()?;
-            let max = versions.max_unyanked_patch(major_version, minor_version);
-            let max = max.cloned();
-            let max = max.filter(|max| max > $crate::crate_version!());
-            Ok(max)
+            $crate::__private::patch_update(
+                &versions,
+                $crate::crate_version!(),
+                $include_prerelease,
+            )
         })
     };
 }
 
+/// Checks if there is an update available, with options.
+///
+/// Without any options, this is the same as `check_max!()`. The options,
+/// which can be given in any order, are:
+///
+/// - `channel = Channel`: which versions to choose from. The default is
+///   `Channel::Stable`, which skips pre-releases like `check_max!()` does.
+///   Use `Channel::Prerelease` to choose from every version that hasn't been
+///   yanked.
+/// - `req = &str`: only choose versions that match this version requirement,
+///   like `"^1"`.
+/// - `timeout = std::time::Duration`: how long to wait for the request.
+///
+/// # Returns
+///
+/// - `Ok(Some(version))` if the max version that meets the options is
+///   greater than the current version
+/// - `Ok(None)` if no version meets the options and is greater than the
+///   current version
+/// - `Err(e)` if comparison could not be made
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use check_latest::{check, Channel};
+/// use std::time::Duration;
+///
+/// let update = check!(
+///     channel = Channel::Stable,
+///     req = "^1",
+///     timeout = Duration::from_secs(5),
+/// );
+/// if let Ok(Some(version)) = update {
+///     println!("A new version is available: {}", version);
+/// }
+/// ```
+#[macro_export]
+macro_rules! check {
+    ($($option:ident = $value:expr),* $(,)?) => {{
+        let mut options = $crate::__private::CheckOptions::new(
+            $crate::crate_name!(),
+            $crate::crate_version!(),
+            $crate::user_agent!(),
+        );
+        $(options.$option($value);)*
+        options.check()
+    }};
+}
+
+/// Defines a `check_self` function in *your* crate.
+///
+/// The generated function is the function-call equivalent of `check_max!()`:
+/// it checks if there is a version of *your* crate available that is greater
+/// than the version that is running. Because it is generated inside your
+/// crate, it picks up the name and version from *your* `Cargo.toml`.
+///
+/// The function is private by default, but a visibility can be passed to the
+/// macro.
+///
+/// ```text
+/// fn check_self() -> Result<Option<Version>>
+/// ```
+///
+/// # Example
+///
+/// ```rust,no_run
+/// check_latest::define_check_self!(pub(crate));
+///
+/// if let Ok(Some(version)) = check_self() {
+///     println!("A new version is available: {}", version);
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_check_self {
+    ($vis:vis) => {
+        /// Checks if there is a newer version of this crate available.
+        $vis fn check_self() -> $crate::__private::Result<Option<$crate::Version>> {
+            $crate::check_max!()
+        }
+    };
+}
+
 impl Versions {
     /// - `crate_name`: The crate that the version should be checked for.
     /// - `user_agent`: without a proper User-Agent, the request to the
@@ -138,6 +259,11 @@ impl Versions {
     /// If you believe you've received this message in error, please email help@crates.io and include the request id {}.
     /// ```
     ///
+    /// # Redirects
+    ///
+    /// Up to 5 redirects will be followed, which lets a renamed crate resolve
+    /// to its new location. Redirects to a different origin (scheme, host, or
+    /// port) are refused and result in an error.
     ///
     /// # Example
     ///
@@ -151,18 +277,399 @@ impl Versions {
     ///
     /// [Crates.io]: https://crates.io/
     pub fn new(crate_name: &str, user_agent: &str) -> Result<Versions> {
-        let url = build_url(crate_name);
-        let response: Versions = reqwest::blocking::Client::builder()
-            .user_agent(user_agent)
-            .build()
-            .context("Couldn't build client")?
-            .get(&url)
+        Versions::fetch(crate_name, &FetchConfig::new(user_agent))
+    }
+    /// Like `new`, but for a registry at `base_url` whose certificate is
+    /// signed by a private CA.
+    ///
+    /// This is a shortcut for `Versions::fetch` with
+    /// `FetchConfig::add_root_certificate`. See that method for how `cert` is
+    /// trusted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let pem = std::fs::read("internal-ca.pem").unwrap();
+    /// let cert = reqwest::Certificate::from_pem(&pem).unwrap();
+    /// let versions = Versions::new_with_root_cert(
+    ///     "https://crates.internal.example.com",
+    ///     "my-cool-crate",
+    ///     "my-cool-crate/1.0.0",
+    ///     cert,
+    /// );
+    /// ```
+    pub fn new_with_root_cert(
+        base_url: &str,
+        crate_name: &str,
+        user_agent: &str,
+        cert: reqwest::Certificate,
+    ) -> Result<Versions> {
+        let config = FetchConfig::new(user_agent)
+            .base_url(base_url)
+            .add_root_certificate(cert);
+        Versions::fetch(crate_name, &config)
+    }
+    /// Like `new`, but tries each registry in `registries`, which are base
+    /// URLs like `"https://crates.io"`, in order.
+    ///
+    /// The versions from the first registry that they can be fetched from are
+    /// returned, and the remaining registries aren't requested. If they can't
+    /// be fetched from any registry, the error includes why each one failed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::from_registries(
+    ///     &["https://crates.internal.example.com", "https://crates.io"],
+    ///     "my-cool-crate",
+    ///     "my-cool-crate/1.0.0",
+    /// );
+    /// ```
+    pub fn from_registries(
+        registries: &[&str],
+        crate_name: &str,
+        user_agent: &str,
+    ) -> Result<Versions> {
+        let mut errors = Vec::new();
+        for registry in registries {
+            let config = FetchConfig::new(user_agent).base_url(registry);
+            match Versions::fetch(crate_name, &config) {
+                Ok(versions) => return Ok(versions),
+                Err(e) => errors.push(format!("{}: {:#}", registry, e)),
+            }
+        }
+        if errors.is_empty() {
+            bail!("No registries to fetch versions from");
+        }
+        bail!(
+            "Couldn't fetch versions from any registry:\n{}",
+            errors.join("\n")
+        )
+    }
+    /// Fetches the versions again, replacing the ones that were found before.
+    ///
+    /// This is useful for long-lived `Versions`, like in a daemon that checks
+    /// for updates periodically. If the request fails, the versions that were
+    /// found before are left intact.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let mut versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// /* Some time later */
+    /// if let Err(e) = versions.refresh("my-cool-crate", "my-cool-crate/1.0.0") {
+    ///     eprintln!("Couldn't refresh versions, using the old ones: {}", e);
+    /// }
+    /// ```
+    pub fn refresh(&mut self, crate_name: &str, user_agent: &str) -> Result<()> {
+        *self = Versions::new(crate_name, user_agent)?;
+        Ok(())
+    }
+    /// Like `new`, but with more control over how the versions are
+    /// fetched.
+    ///
+    /// Any credentials in `config` are attached to the request, which allows
+    /// checking versions on a private registry. Because redirects to another
+    /// origin are refused, credentials are never sent to a different host.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{FetchConfig, Versions};
+    ///
+    /// let config = FetchConfig::new("my-awesome-crate-bin/1.0.0")
+    ///     .basic_auth("my-username", Some("my-password"));
+    /// if let Ok(versions) = Versions::fetch("my-awesome-crate-bin", &config) {
+    ///     /* Do your stuff */
+    /// }
+    /// ```
+    pub fn fetch(crate_name: &str, config: &FetchConfig) -> Result<Versions> {
+        let http = ConfiguredClient::new(config)?;
+        Versions::fetch_with(crate_name, config, &http)
+    }
+    /// Like `fetch`, but can be cancelled from another thread by setting
+    /// `cancel` to `true`.
+    ///
+    /// Cancelling results in a [`Cancelled`] error. If `cancel` is `None`,
+    /// this is the same as `fetch`.
+    ///
+    /// # Cancellation
+    ///
+    /// The request is made once, on another thread, and `cancel` is checked
+    /// every 100 milliseconds while waiting for it. A request can't be
+    /// interrupted while it is being sent or read, so when `cancel` is set,
+    /// this returns right away and the request is left to finish in the
+    /// background. It is still limited by the timeout set in `config`, or the
+    /// client's default timeout of 30 seconds.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{FetchConfig, Versions};
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let cancel = Arc::new(AtomicBool::new(false));
+    /// let worker_cancel = Arc::clone(&cancel);
+    /// let worker = thread::spawn(move || {
+    ///     let config = FetchConfig::new("my-awesome-crate-bin/1.0.0");
+    ///     Versions::fetch_cancellable("my-awesome-crate-bin", &config, Some(worker_cancel))
+    /// });
+    /// // Shutting down...
+    /// cancel.store(true, Ordering::Relaxed);
+    /// let versions = worker.join().unwrap();
+    /// ```
+    pub fn fetch_cancellable(
+        crate_name: &str,
+        config: &FetchConfig,
+        cancel: Option<Arc<AtomicBool>>,
+    ) -> Result<Versions> {
+        let cancel = match cancel {
+            Some(cancel) => cancel,
+            None => return Versions::fetch(crate_name, config),
+        };
+        if cancel.load(Ordering::Relaxed) {
+            return Err(Cancelled.into());
+        }
+        let (sender, receiver) = mpsc::channel();
+        let request = {
+            let crate_name = String::from(crate_name);
+            let config = config.clone();
+            move || {
+                let _ = sender.send(Versions::fetch(&crate_name, &config));
+            }
+        };
+        thread::spawn(request);
+        loop {
+            match receiver.recv_timeout(CANCEL_CHECK_INTERVAL) {
+                Ok(result) => return result,
+                Err(RecvTimeoutError::Timeout) if cancel.load(Ordering::Relaxed) => {
+                    return Err(Cancelled.into());
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => bail!("The request panicked"),
+            }
+        }
+    }
+    /// Like `fetch`, but makes the request with `http` instead of the default
+    /// HTTP client.
+    ///
+    /// Credentials in `config` are *not* used, since attaching them is up to
+    /// `http`. The response size limit in `config` is checked against the
+    /// body that `http` returns, but it is up to `http` to stop reading a
+    /// response that is too large.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::blocking::HttpFetch;
+    /// use check_latest::{FetchConfig, Versions};
+    ///
+    /// let client = reqwest::blocking::Client::new();
+    /// let config = FetchConfig::new("my-awesome-crate-bin/1.0.0");
+    /// if let Ok(versions) = Versions::fetch_with("my-awesome-crate-bin", &config, &client) {
+    ///     /* Do your stuff */
+    /// }
+    /// ```
+    pub fn fetch_with<H>(crate_name: &str, config: &FetchConfig, http: &H) -> Result<Versions>
+    where
+        H: HttpFetch + ?Sized,
+    {
+        let url = build_url(&config.resolved_api_root(), crate_name);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(url = %url, "Requesting crate versions");
+        let body = http.get(&url, config.user_agent()?)?;
+        check_response_size(body.len(), config.response_size_limit())?;
+        Versions::from_fetched(url, &body, config)
+    }
+}
+
+impl CheckOptions {
+    /// Fetches the versions and chooses the update. Used by `check!`.
+    pub fn check(&self) -> Result<Option<Version>> {
+        let versions = Versions::fetch(self.crate_name, &self.config)?;
+        self.select(&versions)
+    }
+}
+
+/// The error when `Versions::fetch_cancellable` is cancelled.
+///
+/// The error is wrapped in an `anyhow::Error`, and can be checked for with
+/// `downcast_ref`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The request was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Makes the HTTP requests needed to fetch versions.
+///
+/// This is implemented for `reqwest::blocking::Client`, and can be implemented
+/// to use another HTTP library, or to return canned responses in tests.
+///
+/// # Example
+///
+/// ```rust
+/// use anyhow::Result;
+/// use check_latest::blocking::HttpFetch;
+/// use check_latest::{FetchConfig, Versions};
+///
+/// struct Canned;
+///
+/// impl HttpFetch for Canned {
+///     fn get(&self, _url: &str, _user_agent: &str) -> Result<String> {
+///         Ok(String::from(r#"{"versions": []}"#))
+///     }
+/// }
+///
+/// let config = FetchConfig::new("my-awesome-crate-bin/1.0.0");
+/// let versions = Versions::fetch_with("my-awesome-crate-bin", &config, &Canned).unwrap();
+/// assert!(versions.versions().is_empty());
+/// ```
+pub trait HttpFetch {
+    /// Sends a GET request to `url`, and returns the body of the response.
+    fn get(&self, url: &str, user_agent: &str) -> Result<String>;
+}
+
+impl HttpFetch for reqwest::blocking::Client {
+    fn get(&self, url: &str, user_agent: &str) -> Result<String> {
+        self.get(url)
+            .header(USER_AGENT, user_agent)
             .send()
             .context("Couldn't request crate info")?
-            .json()
-            .context("Couldn't read as JSON")?;
-        Ok(response)
+            .text()
+            .context("Couldn't read response")
+    }
+}
+
+/// The default HTTP client, which uses the options in a `FetchConfig`.
+struct ConfiguredClient<'a> {
+    client: reqwest::blocking::Client,
+    config: &'a FetchConfig,
+}
+
+impl<'a> ConfiguredClient<'a> {
+    fn new(config: &'a FetchConfig) -> Result<ConfiguredClient<'a>> {
+        let mut builder = reqwest::blocking::Client::builder().redirect(redirect_policy());
+        if let Some(timeout) = config.request_timeout() {
+            builder = builder.timeout(timeout);
+        }
+        for cert in config.root_certificates() {
+            builder = builder.add_root_certificate(cert.clone());
+        }
+        let client = builder.build().context("Couldn't build client")?;
+        Ok(ConfiguredClient { client, config })
+    }
+}
+
+impl HttpFetch for ConfiguredClient<'_> {
+    fn get(&self, url: &str, user_agent: &str) -> Result<String> {
+        let request = self.client.get(url).header(USER_AGENT, user_agent);
+        let request = match &self.config.auth {
+            None => request,
+            Some(Auth::Basic { username, password }) => {
+                request.basic_auth(username, password.as_ref())
+            }
+            Some(Auth::Bearer(token)) => request.bearer_auth(token),
+        };
+        let response = request.send().context("Couldn't request crate info")?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            url = %url,
+            status = %response.status(),
+            content_length = ?response.content_length(),
+            "Received response",
+        );
+        let limit = self.config.response_size_limit();
+        if let Some(length) = response.content_length() {
+            check_response_size(usize::try_from(length).unwrap_or(usize::MAX), limit)?;
+        }
+        read_limited(response, limit)
+    }
+}
+
+/// Reads `reader` to the end, erroring as soon as more than `limit` bytes have
+/// been read.
+fn read_limited<R: Read>(reader: R, limit: usize) -> Result<String> {
+    let mut body = Vec::new();
+    reader
+        .take((limit as u64).saturating_add(1))
+        .read_to_end(&mut body)
+        .context("Couldn't read response")?;
+    check_response_size(body.len(), limit)?;
+    String::from_utf8(body).context("Couldn't read response")
+}
+
+/// Fetches the versions of many crates, one at a time.
+///
+/// After each crate is checked, `progress` is called with the number of
+/// crates that have been checked so far and the total number of crates, which
+/// can be used to update a progress bar.
+///
+/// The results are keyed by crate name.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use check_latest::blocking::check_many;
+///
+/// let crate_names = ["serde", "tokio", "anyhow"];
+/// let results = check_many(&crate_names, "my-cool-crate/1.0.0", |done, total| {
+///     println!("Checked {}/{}", done, total);
+/// });
+/// for (crate_name, versions) in results {
+///     if let Ok(versions) = versions {
+///         println!("{}: {:?}", crate_name, versions.max_stable_version());
+///     }
+/// }
+/// ```
+pub fn check_many<I, S, F>(
+    crate_names: I,
+    user_agent: &str,
+    progress: F,
+) -> HashMap<String, Result<Versions>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+    F: FnMut(usize, usize),
+{
+    let crate_names: Vec<S> = crate_names.into_iter().collect();
+    fetch_many(&crate_names, &FetchConfig::new(user_agent), progress)
+}
+
+/// Fetches the versions of each crate with `config`, for `check_many`.
+fn fetch_many<S, F>(
+    crate_names: &[S],
+    config: &FetchConfig,
+    mut progress: F,
+) -> HashMap<String, Result<Versions>>
+where
+    S: AsRef<str>,
+    F: FnMut(usize, usize),
+{
+    let total = crate_names.len();
+    let mut checked = HashMap::with_capacity(total);
+    let mut done = 0;
+    for crate_name in crate_names {
+        let crate_name = String::from(crate_name.as_ref());
+        let versions = Versions::fetch(&crate_name, config);
+        checked.insert(crate_name, versions);
+        done += 1;
+        progress(done, total);
     }
+    checked
 }
 
 /// Helper for creating a new `Versions`.
@@ -215,3 +722,239 @@ macro_rules! new_versions {
         )
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ResponseTooLarge;
+    use std::cell::RefCell;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Instant;
+
+    struct Canned {
+        body: &'static str,
+        requested: RefCell<Vec<(String, String)>>,
+    }
+
+    impl HttpFetch for Canned {
+        fn get(&self, url: &str, user_agent: &str) -> Result<String> {
+            self.requested
+                .borrow_mut()
+                .push((String::from(url), String::from(user_agent)));
+            Ok(String::from(self.body))
+        }
+    }
+
+    #[test]
+    fn fetch_cancelled() {
+        let config = FetchConfig::new("my-crate/1.0.0").base_url("http://localhost:1");
+        let cancel = Arc::new(AtomicBool::new(true));
+        let e = Versions::fetch_cancellable("some-crate", &config, Some(cancel)).unwrap_err();
+        assert_eq!(e.downcast_ref::<Cancelled>(), Some(&Cancelled));
+    }
+
+    /// Starts a server that responds to each request with `respond(path)`,
+    /// which returns the whole HTTP response, and returns its base URL.
+    fn serve_with<F>(respond: F) -> String
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let respond = Arc::new(respond);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let respond = Arc::clone(&respond);
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(&stream);
+                    let mut request_line = String::new();
+                    let _ = reader.read_line(&mut request_line);
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                        line.clear();
+                    }
+                    let path = request_line.split(' ').nth(1).unwrap_or("/");
+                    let _ = write!(stream, "{}", respond(path));
+                });
+            }
+        });
+        url
+    }
+
+    /// A response with one version.
+    fn ok_response() -> String {
+        let body = r#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body,
+        )
+    }
+
+    /// A response that redirects to `location`.
+    fn redirect_response(location: &str) -> String {
+        format!(
+            "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            location,
+        )
+    }
+
+    /// Starts a server that waits `delay` before responding to each request,
+    /// and counts the requests.
+    fn slow_server(delay: Duration) -> (String, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let url = serve_with(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(delay);
+            ok_response()
+        });
+        (url, requests)
+    }
+
+    /// Starts a server that redirects `hops` times on the same origin before
+    /// responding.
+    fn redirecting_server(hops: usize) -> String {
+        serve_with(move |path| match path.strip_prefix("/hop/") {
+            Some(hop) if hop.parse::<usize>().unwrap() >= hops => ok_response(),
+            Some(hop) => redirect_response(&format!("/hop/{}", hop.parse::<usize>().unwrap() + 1)),
+            None if hops == 0 => ok_response(),
+            None => redirect_response("/hop/1"),
+        })
+    }
+
+    #[test]
+    fn follows_same_origin_redirects() {
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&redirecting_server(5));
+        let versions = Versions::fetch("some-crate", &config).unwrap();
+        assert_eq!(versions.max_version().unwrap(), &"1.0.0");
+    }
+
+    #[test]
+    fn too_many_redirects() {
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&redirecting_server(6));
+        let e = Versions::fetch("some-crate", &config).unwrap_err();
+        assert!(format!("{:#}", e).contains("too many redirects"));
+    }
+
+    #[test]
+    fn refuses_cross_origin_redirect() {
+        let other = serve_with(|_| ok_response());
+        let url = serve_with(move |_| redirect_response(&format!("{}/elsewhere", other)));
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&url);
+        let e = Versions::fetch("some-crate", &config).unwrap_err();
+        assert!(format!("{:#}", e).contains("another origin"));
+    }
+
+    #[test]
+    fn fetch_cancellable_waits_for_one_request() {
+        let (url, requests) = slow_server(CANCEL_CHECK_INTERVAL * 5);
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&url);
+        let cancel = Arc::new(AtomicBool::new(false));
+        let versions = Versions::fetch_cancellable("some-crate", &config, Some(cancel)).unwrap();
+        assert_eq!(versions.max_version().unwrap(), &"1.0.0");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn fetch_cancellable_while_waiting() {
+        let (url, _) = slow_server(Duration::from_secs(10));
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&url);
+        let cancel = Arc::new(AtomicBool::new(false));
+        let canceller = {
+            let cancel = Arc::clone(&cancel);
+            thread::spawn(move || {
+                thread::sleep(CANCEL_CHECK_INTERVAL * 2);
+                cancel.store(true, Ordering::Relaxed);
+            })
+        };
+        let start = Instant::now();
+        let e = Versions::fetch_cancellable("some-crate", &config, Some(cancel)).unwrap_err();
+        assert_eq!(e.downcast_ref::<Cancelled>(), Some(&Cancelled));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        canceller.join().unwrap();
+    }
+
+    #[test]
+    fn from_registries_fails_with_every_error() {
+        let registries = ["http://localhost:1", "http://localhost:2"];
+        let e = Versions::from_registries(&registries, "some-crate", "my-crate/1.0.0")
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("http://localhost:1: "));
+        assert!(e.contains("http://localhost:2: "));
+        assert!(Versions::from_registries(&[], "some-crate", "my-crate/1.0.0").is_err());
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn from_registries_falls_back() {
+        let body = r#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+        let (url, _server) = crate::test_util::serve(&Versions::from_json(body).unwrap());
+        let registries = ["http://localhost:1", url.as_str()];
+        let versions =
+            Versions::from_registries(&registries, "some-crate", "my-crate/1.0.0").unwrap();
+        assert_eq!(versions.max_version().unwrap(), &"1.0.0");
+        assert_eq!(
+            versions.source_url(),
+            Some(format!("{}/api/v1/crates/some-crate", url).as_str())
+        );
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn fetch_many_progress() {
+        let body = r#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+        let (url, _server) = crate::test_util::serve(&Versions::from_json(body).unwrap());
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&url);
+        let mut calls = Vec::new();
+        let checked = fetch_many(&["a", "b", "c"], &config, |done, total| {
+            calls.push((done, total));
+        });
+        assert_eq!(calls, vec![(1, 3), (2, 3), (3, 3)]);
+        assert_eq!(checked.len(), 3);
+        assert!(checked.values().all(Result::is_ok));
+    }
+
+    #[test]
+    fn read_limited() {
+        let body = super::read_limited(&b"{}"[..], 2).unwrap();
+        assert_eq!(body, "{}");
+        let e = super::read_limited(&b"{ }"[..], 2).unwrap_err();
+        assert_eq!(
+            e.downcast_ref::<ResponseTooLarge>(),
+            Some(&ResponseTooLarge { limit: 2 })
+        );
+    }
+
+    #[test]
+    fn fetches_with_custom_http() {
+        let http = Canned {
+            body: r#"{"versions": [
+                {"num": "1.0.0", "yanked": false, "created_at": "2020-01-01T00:00:00Z"},
+                {"num": "1.1.0", "yanked": true, "created_at": "2020-02-01T00:00:00Z"}
+            ]}"#,
+            requested: RefCell::new(Vec::new()),
+        };
+        let config = FetchConfig::new("my-crate/1.0.0");
+        let versions = Versions::fetch_with("some-crate", &config, &http).unwrap();
+
+        assert_eq!(versions.versions().len(), 2);
+        assert_eq!(
+            versions.source_url(),
+            Some("https://crates.io/api/v1/crates/some-crate")
+        );
+        assert_eq!(versions.url(), versions.source_url());
+        assert_eq!(versions.max_unyanked_version().unwrap(), &"1.0.0");
+        assert_eq!(
+            http.requested.into_inner(),
+            vec![(
+                String::from("https://crates.io/api/v1/crates/some-crate"),
+                String::from("my-crate/1.0.0"),
+            )]
+        );
+    }
+}
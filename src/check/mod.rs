@@ -0,0 +1,90 @@
+//! The options for the `check!` and `check_async!` macros.
+
+use crate::{parse_version_lenient, Channel, FetchConfig, Version, Versions};
+use anyhow::{anyhow, Context, Result};
+use semver::VersionReq;
+use std::time::Duration;
+
+/// The options passed to `check!` or `check_async!`.
+pub struct CheckOptions {
+    pub(crate) crate_name: &'static str,
+    current: &'static str,
+    pub(crate) config: FetchConfig,
+    channel: Channel,
+    req: Option<String>,
+}
+
+impl CheckOptions {
+    /// Creates the default options for a crate.
+    pub fn new(
+        crate_name: &'static str,
+        current: &'static str,
+        user_agent: &'static str,
+    ) -> CheckOptions {
+        CheckOptions {
+            crate_name,
+            current,
+            config: FetchConfig::new(user_agent),
+            channel: Channel::Stable,
+            req: None,
+        }
+    }
+    /// Sets the `channel` option.
+    pub fn channel(&mut self, channel: Channel) {
+        self.channel = channel;
+    }
+    /// Sets the `req` option.
+    pub fn req(&mut self, req: &str) {
+        self.req = Some(String::from(req));
+    }
+    /// Sets the `timeout` option.
+    pub fn timeout(&mut self, timeout: Duration) {
+        self.config = std::mem::take(&mut self.config).timeout(timeout);
+    }
+    /// Chooses the update from the fetched `versions`.
+    pub(crate) fn select(&self, versions: &Versions) -> Result<Option<Version>> {
+        let current =
+            parse_version_lenient(self.current).context("Couldn't parse current version")?;
+        let choose = |versions: &Versions| {
+            versions
+                .max_in_channel(&self.channel)
+                .filter(|max| max.version > current)
+                .cloned()
+        };
+        match &self.req {
+            Some(req) => {
+                let req = VersionReq::parse(req)
+                    .map_err(|e| anyhow!("Couldn't parse version requirement: {}", e))?;
+                Ok(choose(&versions.filter(|v| req.matches(&v.version))))
+            }
+            None => Ok(choose(versions)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::versions;
+
+    #[test]
+    fn check_options() {
+        let list = versions(&[
+            ("1.0.0", false),
+            ("1.1.0", false),
+            ("1.2.0-rc.1", false),
+            ("2.0.0", false),
+            ("2.1.0", true),
+        ]);
+        let mut options = CheckOptions::new("my-crate", "1.0.0", "my-crate/1.0.0");
+        assert_eq!(options.select(&list).unwrap().unwrap(), "2.0.0");
+        options.req("^1");
+        assert_eq!(options.select(&list).unwrap().unwrap(), "1.1.0");
+        options.req(">=1.2.0-rc.1, <2");
+        assert!(options.select(&list).unwrap().is_none());
+        options.channel(Channel::Prerelease);
+        assert_eq!(options.select(&list).unwrap().unwrap(), "1.2.0-rc.1");
+        options.req("not a requirement");
+        assert!(options.select(&list).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
+use std::fmt;
+use std::time::Duration;
+
+/// The registry that is used if no other base URL is set.
+const CRATES_IO: &str = "https://crates.io";
+
+/// The largest response that is read if no other limit is set, in bytes.
+#[cfg_attr(not(any(feature = "async", feature = "blocking")), allow(dead_code))]
+const DEFAULT_MAX_RESPONSE_SIZE: usize = 8 * 1024 * 1024;
+
+static DEFAULT_USER_AGENT: OnceCell<String> = OnceCell::new();
+
+/// Sets the user agent that is used when a `FetchConfig` doesn't have one.
+///
+/// The default user agent can only be set once for the whole process. Calling
+/// this again returns an error and leaves the first user agent in place. It is
+/// safe to call from multiple threads, and exactly one call will succeed.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use check_latest::{set_default_user_agent, user_agent, FetchConfig, Versions};
+///
+/// set_default_user_agent(user_agent!()).unwrap();
+/// let config = FetchConfig::default();
+/// let serde = Versions::fetch("serde", &config);
+/// let tokio = Versions::fetch("tokio", &config);
+/// ```
+pub fn set_default_user_agent(user_agent: &str) -> Result<()> {
+    set_user_agent(&DEFAULT_USER_AGENT, user_agent)
+}
+
+/// Sets `cell` to `user_agent`, unless it has already been set.
+fn set_user_agent(cell: &OnceCell<String>, user_agent: &str) -> Result<()> {
+    cell.set(String::from(user_agent))
+        .map_err(|_| anyhow!("The default user agent has already been set"))
+}
+
+/// Options for fetching a crate's versions.
+///
+/// `FetchConfig::default()` has no user agent, so it uses the one set with
+/// [`set_default_user_agent`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use check_latest::{FetchConfig, Versions};
+///
+/// let config = FetchConfig::new("my-cool-crate/1.0.0").bearer_token("my-secret-token");
+/// let versions = Versions::fetch("my-cool-crate", &config);
+/// ```
+#[derive(Clone, Default)]
+pub struct FetchConfig {
+    user_agent: Option<String>,
+    pub(crate) auth: Option<Auth>,
+    base_url: Option<String>,
+    path_prefix: Option<String>,
+    api_root: Option<String>,
+    max_response_size: Option<usize>,
+    lenient_versions: bool,
+    timeout: Option<Duration>,
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    root_certificates: Vec<reqwest::Certificate>,
+}
+
+/// Credentials to attach to the request.
+#[derive(Clone)]
+#[cfg_attr(not(any(feature = "async", feature = "blocking")), allow(dead_code))]
+pub(crate) enum Auth {
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    Bearer(String),
+}
+
+impl FetchConfig {
+    /// Creates a new config with the default options.
+    ///
+    /// See `Versions::new` for why `user_agent` is needed.
+    pub fn new(user_agent: &str) -> FetchConfig {
+        FetchConfig {
+            user_agent: Some(String::from(user_agent)),
+            ..FetchConfig::default()
+        }
+    }
+    /// Gets the user agent to send, falling back to the default user agent.
+    #[cfg_attr(not(any(feature = "async", feature = "blocking")), allow(dead_code))]
+    pub(crate) fn user_agent(&self) -> Result<&str> {
+        self.user_agent_or(DEFAULT_USER_AGENT.get().map(String::as_str))
+    }
+    /// Gets the user agent to send, falling back to `default`.
+    #[cfg_attr(not(any(feature = "async", feature = "blocking")), allow(dead_code))]
+    fn user_agent_or<'a>(&'a self, default: Option<&'a str>) -> Result<&'a str> {
+        self.user_agent
+            .as_deref()
+            .or(default)
+            .ok_or_else(|| anyhow!("No user agent was set"))
+    }
+    /// Sets the base URL of the registry, like `"https://crates.io"`.
+    ///
+    /// The default is [Crates.io].
+    ///
+    /// [Crates.io]: https://crates.io/
+    pub fn base_url(mut self, base_url: &str) -> FetchConfig {
+        self.base_url = Some(String::from(base_url));
+        self
+    }
+    /// Sets a path prefix that is inserted between the base URL and
+    /// `api/v1`.
+    ///
+    /// This is useful for proxies that serve the registry's API under a
+    /// path. For example, with a base URL of `"https://proxy.example.com"`
+    /// and a path prefix of `"crates-io"`, versions are fetched from
+    /// `https://proxy.example.com/crates-io/api/v1/crates/<crate_name>`.
+    pub fn path_prefix(mut self, path_prefix: &str) -> FetchConfig {
+        self.path_prefix = Some(String::from(path_prefix));
+        self
+    }
+    /// Sets the full root of the registry's API, like
+    /// `"https://host/nexus/repository/cargo/api/v1"`.
+    ///
+    /// This is useful for registries that implement the [Crates.io] API, but
+    /// not at `api/v1`. Versions are fetched from
+    /// `<api_root>/crates/<crate_name>`. If this is set, the base URL and
+    /// path prefix are ignored.
+    ///
+    /// [Crates.io]: https://crates.io/
+    pub fn api_root(mut self, api_root: &str) -> FetchConfig {
+        self.api_root = Some(String::from(api_root));
+        self
+    }
+    /// Gets the root of the registry's API, without a trailing slash.
+    pub(crate) fn resolved_api_root(&self) -> String {
+        if let Some(api_root) = &self.api_root {
+            return String::from(api_root.trim_end_matches('/'));
+        }
+        let base_url = self
+            .base_url
+            .as_deref()
+            .unwrap_or(CRATES_IO)
+            .trim_end_matches('/');
+        let path_prefix = self
+            .path_prefix
+            .as_deref()
+            .map(|prefix| prefix.trim_matches('/'))
+            .filter(|prefix| !prefix.is_empty());
+        match path_prefix {
+            Some(path_prefix) => format!("{}/{}/api/v1", base_url, path_prefix),
+            None => format!("{}/api/v1", base_url),
+        }
+    }
+    /// Sets the largest response that will be read, in bytes.
+    ///
+    /// A larger response results in a [`ResponseTooLarge`] error, instead of
+    /// being read into memory. The default is 8 MiB.
+    ///
+    /// [`ResponseTooLarge`]: crate::ResponseTooLarge
+    pub fn max_response_size(mut self, max_response_size: usize) -> FetchConfig {
+        self.max_response_size = Some(max_response_size);
+        self
+    }
+    /// Gets the largest response that will be read, in bytes.
+    #[cfg_attr(not(any(feature = "async", feature = "blocking")), allow(dead_code))]
+    pub(crate) fn response_size_limit(&self) -> usize {
+        self.max_response_size.unwrap_or(DEFAULT_MAX_RESPONSE_SIZE)
+    }
+    /// Sets if versions that aren't valid SemVer should be parsed leniently.
+    ///
+    /// Lenient parsing accepts a leading `v`, like `v1.2.3`, and leading
+    /// zeros, like `01.02.03`, which some registries use. Versions still need
+    /// a MAJOR, MINOR, and PATCH version.
+    ///
+    /// Publish dates are parsed leniently too, accepting a space instead of
+    /// the `T` in `2020-01-01T00:00:00Z`, or a missing time zone, which is
+    /// assumed to be UTC. A publish date that can't be parsed at all is
+    /// treated as unknown, instead of failing the whole response.
+    ///
+    /// This also applies to `Versions::from_index_file_with`.
+    ///
+    /// The default is strict parsing.
+    pub fn lenient_versions(mut self, lenient_versions: bool) -> FetchConfig {
+        self.lenient_versions = lenient_versions;
+        self
+    }
+    /// Checks if versions should be parsed leniently.
+    pub(crate) fn is_lenient(&self) -> bool {
+        self.lenient_versions
+    }
+    /// Sets how long to wait for a request to complete.
+    ///
+    /// The default is to wait as long as the HTTP client does.
+    pub fn timeout(mut self, timeout: Duration) -> FetchConfig {
+        self.timeout = Some(timeout);
+        self
+    }
+    /// Gets how long to wait for a request to complete, if it was set.
+    #[cfg_attr(not(any(feature = "async", feature = "blocking")), allow(dead_code))]
+    pub(crate) fn request_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+    /// Trusts an additional root certificate, like the private CA of an
+    /// internal mirror or a corporate proxy.
+    ///
+    /// Requests are made with [rustls], which trusts the [webpki-roots] by
+    /// default. Certificates added here are trusted in addition to those, not
+    /// instead of them. The system's certificate store is *not* used, so a
+    /// private CA must always be added here.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{FetchConfig, Versions};
+    ///
+    /// let pem = std::fs::read("internal-ca.pem").unwrap();
+    /// let cert = reqwest::Certificate::from_pem(&pem).unwrap();
+    /// let config = FetchConfig::new("my-cool-crate/1.0.0")
+    ///     .base_url("https://crates.internal.example.com")
+    ///     .add_root_certificate(cert);
+    /// let versions = Versions::fetch("my-cool-crate", &config);
+    /// ```
+    ///
+    /// [rustls]: https://docs.rs/rustls
+    /// [webpki-roots]: https://docs.rs/webpki-roots
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub fn add_root_certificate(mut self, cert: reqwest::Certificate) -> FetchConfig {
+        self.root_certificates.push(cert);
+        self
+    }
+    /// Gets the additional root certificates to trust.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) fn root_certificates(&self) -> &[reqwest::Certificate] {
+        &self.root_certificates
+    }
+    /// Authenticates with HTTP basic authentication.
+    ///
+    /// Replaces any credentials that were previously set.
+    pub fn basic_auth(mut self, username: &str, password: Option<&str>) -> FetchConfig {
+        self.auth = Some(Auth::Basic {
+            username: String::from(username),
+            password: password.map(String::from),
+        });
+        self
+    }
+    /// Authenticates with a bearer token.
+    ///
+    /// Replaces any credentials that were previously set.
+    pub fn bearer_token(mut self, token: &str) -> FetchConfig {
+        self.auth = Some(Auth::Bearer(String::from(token)));
+        self
+    }
+}
+
+/// Credentials are redacted so that they don't end up in logs.
+impl fmt::Debug for FetchConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let auth = match self.auth {
+            None => "None",
+            Some(Auth::Basic { .. }) => "Basic(<redacted>)",
+            Some(Auth::Bearer(_)) => "Bearer(<redacted>)",
+        };
+        let mut debug = f.debug_struct("FetchConfig");
+        debug
+            .field("user_agent", &self.user_agent)
+            .field("auth", &format_args!("{}", auth))
+            .field("base_url", &self.base_url)
+            .field("path_prefix", &self.path_prefix)
+            .field("api_root", &self.api_root)
+            .field("max_response_size", &self.max_response_size)
+            .field("lenient_versions", &self.lenient_versions)
+            .field("timeout", &self.timeout);
+        #[cfg(any(feature = "async", feature = "blocking"))]
+        debug.field("root_certificates", &self.root_certificates.len());
+        debug.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_url;
+
+    #[test]
+    fn default_url() {
+        let config = FetchConfig::new("my-crate/1.0.0");
+        assert_eq!(
+            build_url(&config.resolved_api_root(), "serde"),
+            "https://crates.io/api/v1/crates/serde"
+        );
+    }
+
+    #[test]
+    fn url_with_path_prefix() {
+        let config = FetchConfig::new("my-crate/1.0.0")
+            .base_url("https://proxy.example.com/")
+            .path_prefix("/crates-io/");
+        assert_eq!(
+            build_url(&config.resolved_api_root(), "serde"),
+            "https://proxy.example.com/crates-io/api/v1/crates/serde"
+        );
+    }
+
+    #[test]
+    fn url_with_api_root() {
+        let config = FetchConfig::new("my-crate/1.0.0")
+            .base_url("https://proxy.example.com")
+            .api_root("https://host.example.com/nexus/repository/cargo/api/v1/");
+        assert_eq!(
+            build_url(&config.resolved_api_root(), "serde"),
+            "https://host.example.com/nexus/repository/cargo/api/v1/crates/serde"
+        );
+    }
+
+    #[test]
+    fn user_agent_fallback() {
+        let config = FetchConfig::new("my-crate/1.0.0");
+        assert_eq!(config.user_agent_or(None).unwrap(), "my-crate/1.0.0");
+        assert_eq!(
+            config.user_agent_or(Some("default/1.0.0")).unwrap(),
+            "my-crate/1.0.0"
+        );
+
+        let config = FetchConfig::default();
+        assert!(config.user_agent_or(None).is_err());
+        assert_eq!(
+            config.user_agent_or(Some("default/1.0.0")).unwrap(),
+            "default/1.0.0"
+        );
+    }
+
+    #[test]
+    fn set_user_agent_once() {
+        let cell = OnceCell::new();
+        set_user_agent(&cell, "default/1.0.0").unwrap();
+        assert!(set_user_agent(&cell, "other/1.0.0").is_err());
+        assert_eq!(cell.get().unwrap(), "default/1.0.0");
+    }
+
+    #[test]
+    fn debug_redacts_credentials() {
+        let config = FetchConfig::new("my-crate/1.0.0").basic_auth("me", Some("hunter2"));
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("Basic(<redacted>)"));
+
+        let config = config.bearer_token("s3cr3t");
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("s3cr3t"));
+        assert!(debug.contains("Bearer(<redacted>)"));
+    }
+}
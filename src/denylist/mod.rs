@@ -0,0 +1,68 @@
+//! Support for denylists of versions that shouldn't be recommended, like
+//! versions with known vulnerabilities.
+//!
+//! A denylist file contains one version per line. Blank lines, and lines
+//! starting with `#`, are skipped.
+
+use anyhow::{anyhow, Context, Result};
+use semver::Version as SemVer;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Parses the contents of a denylist file.
+pub(crate) fn parse(contents: &str) -> Result<HashSet<SemVer>> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            SemVer::parse(line)
+                .map_err(|e| anyhow!("Couldn't parse denylist line {}: {}", index + 1, e))
+        })
+        .collect()
+}
+
+/// Reads a set of denied versions from a file, for use with
+/// `Versions::max_unyanked_version_except`.
+///
+/// The file contains one version per line. Blank lines, and lines starting
+/// with `#`, are skipped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use check_latest::{load_denylist, Versions};
+///
+/// let deny = load_denylist("denied-versions.txt").unwrap();
+/// let allowed = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+///     .unwrap()
+///     .max_unyanked_version_except(&deny);
+/// ```
+pub fn load_denylist<P: AsRef<Path>>(path: P) -> Result<HashSet<SemVer>> {
+    let contents = fs::read_to_string(path).context("Couldn't read denylist file")?;
+    parse(&contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_skips_comments_and_blank_lines() {
+        let contents = "# Known vulnerable\n1.0.0\n\n  1.2.3-rc.1  \n# 2.0.0\n";
+        let deny = parse(contents).unwrap();
+        let expected: HashSet<SemVer> = ["1.0.0", "1.2.3-rc.1"]
+            .iter()
+            .map(|v| SemVer::parse(v).unwrap())
+            .collect();
+        assert_eq!(deny, expected);
+    }
+
+    #[test]
+    fn parse_reports_bad_line() {
+        let e = parse("1.0.0\nnot-a-version\n").unwrap_err();
+        assert!(e.to_string().starts_with("Couldn't parse denylist line 2"));
+    }
+}
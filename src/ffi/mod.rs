@@ -0,0 +1,164 @@
+//! Enabled with the `ffi` feature
+//!
+//! A minimal C ABI for checking the max version of a crate from a non-Rust
+//! application. Build a C library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or
+//! `staticlib`), and declare the function in C as:
+//!
+//! ```c
+//! int check_latest_get_max(const char *crate_name, const char *user_agent,
+//!                          char *out_version_buf, size_t buf_len);
+//! ```
+
+use crate::Versions;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// The version was written to the buffer.
+pub const CHECK_LATEST_OK: c_int = 0;
+/// An argument was a null pointer, or wasn't valid UTF-8.
+pub const CHECK_LATEST_INVALID_ARGUMENT: c_int = -1;
+/// The versions couldn't be fetched.
+pub const CHECK_LATEST_FETCH_FAILED: c_int = -2;
+/// Every version of the crate has been yanked.
+pub const CHECK_LATEST_NO_VERSION: c_int = -3;
+/// The buffer is too small for the version and its NUL terminator.
+pub const CHECK_LATEST_BUFFER_TOO_SMALL: c_int = -4;
+/// Something panicked while checking. The panic is caught instead of
+/// unwinding into the caller.
+pub const CHECK_LATEST_PANIC: c_int = -5;
+
+/// Fetches the max version of `crate_name` that hasn't been yanked, and
+/// writes it to `out_version_buf` as a NUL-terminated string, like
+/// `"1.2.3"`.
+///
+/// This makes a blocking request, like `Versions::new`.
+///
+/// # Buffer Contract
+///
+/// `buf_len` is the size of `out_version_buf` in bytes, including room for
+/// the NUL terminator. Nothing is written to the buffer unless
+/// `CHECK_LATEST_OK` is returned. 64 bytes is enough for almost any version.
+///
+/// # Returns
+///
+/// - `CHECK_LATEST_OK` (`0`) if the version was written
+/// - `CHECK_LATEST_INVALID_ARGUMENT` (`-1`) if an argument is null or isn't
+///   valid UTF-8
+/// - `CHECK_LATEST_FETCH_FAILED` (`-2`) if the versions couldn't be fetched
+/// - `CHECK_LATEST_NO_VERSION` (`-3`) if every version has been yanked
+/// - `CHECK_LATEST_BUFFER_TOO_SMALL` (`-4`) if the version doesn't fit
+/// - `CHECK_LATEST_PANIC` (`-5`) if something panicked. Panics never unwind
+///   across this function.
+///
+/// # Safety
+///
+/// `crate_name` and `user_agent` must be null or point to NUL-terminated
+/// strings. `out_version_buf` must be null or point to at least `buf_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn check_latest_get_max(
+    crate_name: *const c_char,
+    user_agent: *const c_char,
+    out_version_buf: *mut c_char,
+    buf_len: usize,
+) -> c_int {
+    catch_panic(|| get_max(crate_name, user_agent, out_version_buf, buf_len))
+}
+
+/// Calls `f`, returning `CHECK_LATEST_PANIC` if it panics.
+fn catch_panic<F: FnOnce() -> c_int>(f: F) -> c_int {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(CHECK_LATEST_PANIC)
+}
+
+/// The body of `check_latest_get_max`, which may panic.
+///
+/// # Safety
+///
+/// See `check_latest_get_max`.
+unsafe fn get_max(
+    crate_name: *const c_char,
+    user_agent: *const c_char,
+    out_version_buf: *mut c_char,
+    buf_len: usize,
+) -> c_int {
+    if out_version_buf.is_null() {
+        return CHECK_LATEST_INVALID_ARGUMENT;
+    }
+    let (crate_name, user_agent) = match (to_str(crate_name), to_str(user_agent)) {
+        (Some(crate_name), Some(user_agent)) => (crate_name, user_agent),
+        _ => return CHECK_LATEST_INVALID_ARGUMENT,
+    };
+    let versions = match Versions::new(crate_name, user_agent) {
+        Ok(versions) => versions,
+        Err(_) => return CHECK_LATEST_FETCH_FAILED,
+    };
+    match versions.max_unyanked_version() {
+        Some(max) => write_version(&max.version.to_string(), out_version_buf, buf_len),
+        None => CHECK_LATEST_NO_VERSION,
+    }
+}
+
+/// Borrows a C string as a `str`.
+///
+/// # Safety
+///
+/// `s` must be null or point to a NUL-terminated string.
+unsafe fn to_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Writes `version` and a NUL terminator to `buf`, if it fits.
+///
+/// # Safety
+///
+/// `buf` must point to at least `buf_len` writable bytes.
+unsafe fn write_version(version: &str, buf: *mut c_char, buf_len: usize) -> c_int {
+    let bytes = version.as_bytes();
+    if bytes.len() >= buf_len {
+        return CHECK_LATEST_BUFFER_TOO_SMALL;
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr().cast::<c_char>(), buf, bytes.len());
+    *buf.add(bytes.len()) = 0;
+    CHECK_LATEST_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_version() {
+        let mut buf = [1 as c_char; 6];
+        let code = unsafe { super::write_version("1.2.3", buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(code, CHECK_LATEST_OK);
+        let written = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        assert_eq!(written.to_str().unwrap(), "1.2.3");
+
+        let mut buf = [1 as c_char; 5];
+        let code = unsafe { super::write_version("1.2.3", buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(code, CHECK_LATEST_BUFFER_TOO_SMALL);
+        assert_eq!(buf, [1; 5]);
+    }
+
+    #[test]
+    fn catch_panic() {
+        assert_eq!(super::catch_panic(|| CHECK_LATEST_OK), CHECK_LATEST_OK);
+        assert_eq!(super::catch_panic(|| panic!("oops")), CHECK_LATEST_PANIC);
+    }
+
+    #[test]
+    fn null_arguments() {
+        let mut buf = [0 as c_char; 64];
+        let name = b"serde\0".as_ptr().cast::<c_char>();
+        let code = unsafe { check_latest_get_max(ptr::null(), name, buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(code, CHECK_LATEST_INVALID_ARGUMENT);
+        let code = unsafe { check_latest_get_max(name, name, ptr::null_mut(), 64) };
+        assert_eq!(code, CHECK_LATEST_INVALID_ARGUMENT);
+    }
+}
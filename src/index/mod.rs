@@ -0,0 +1,148 @@
+//! Support for the [sparse index] format used by Cargo registries.
+//!
+//! An index file contains one JSON object per line, one for each published
+//! version of a crate.
+//!
+//! [sparse index]: https://doc.rust-lang.org/cargo/reference/registry-index.html
+
+use crate::{normalize_version, parse_rust_version, FetchConfig, Version, Versions};
+use anyhow::{Context, Result};
+use semver::Version as SemVer;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// A single line of an index file.
+///
+/// Index records don't include when a version was published, or how many
+/// times it was downloaded.
+#[derive(Deserialize)]
+struct IndexRecord {
+    vers: SemVer,
+    yanked: bool,
+    rust_version: Option<String>,
+}
+
+impl From<IndexRecord> for Version {
+    fn from(record: IndexRecord) -> Version {
+        Version {
+            version: record.vers,
+            yanked: record.yanked,
+            created_at: None,
+            downloads: None,
+            rust_version: record.rust_version.as_deref().and_then(parse_rust_version),
+            yank_message: None,
+        }
+    }
+}
+
+/// Parses the contents of an index file, skipping blank lines.
+///
+/// If `lenient` is set, versions that aren't valid SemVer are parsed with
+/// [`parse_version_lenient`](crate::parse_version_lenient).
+pub(crate) fn parse(contents: &str, lenient: bool) -> Result<Vec<Version>> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            parse_line(line, lenient)
+                .map(Version::from)
+                .with_context(|| format!("Couldn't parse index line {}", index + 1))
+        })
+        .collect()
+}
+
+/// Parses a single line of an index file.
+fn parse_line(line: &str, lenient: bool) -> serde_json::Result<IndexRecord> {
+    if !lenient {
+        return serde_json::from_str(line);
+    }
+    let mut value: Value = serde_json::from_str(line)?;
+    normalize_version(value.get_mut("vers"));
+    serde_json::from_value(value)
+}
+
+impl Versions {
+    /// Reads versions from a local copy of a crate's [sparse index] file.
+    ///
+    /// This allows checking for versions without any network access, for
+    /// example against a mirrored registry index.
+    ///
+    /// *__NOTE__ Index files don't record when a version was published, so
+    /// `created_at` will be `None` for every version.*
+    ///
+    /// Versions are parsed strictly. Use `from_index_file_with` to parse them
+    /// leniently.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// if let Ok(versions) = Versions::from_index_file("index/my/-c/my-cool-crate") {
+    ///     /* Do your stuff */
+    /// }
+    /// ```
+    ///
+    /// [sparse index]: https://doc.rust-lang.org/cargo/reference/registry-index.html
+    pub fn from_index_file<P: AsRef<Path>>(path: P) -> Result<Versions> {
+        Versions::from_index_file_with(path, &FetchConfig::default())
+    }
+    /// Like `from_index_file`, but versions are parsed leniently if
+    /// `config` has `FetchConfig::lenient_versions` set. The other options in
+    /// `config` are ignored, since nothing is fetched.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{FetchConfig, Versions};
+    ///
+    /// let config = FetchConfig::default().lenient_versions(true);
+    /// if let Ok(versions) = Versions::from_index_file_with("index/my/-c/my-cool-crate", &config) {
+    ///     /* Do your stuff */
+    /// }
+    /// ```
+    pub fn from_index_file_with<P: AsRef<Path>>(path: P, config: &FetchConfig) -> Result<Versions> {
+        let contents = fs::read_to_string(path).context("Couldn't read index file")?;
+        let versions = parse(&contents, config.is_lenient())?;
+        Ok(Versions::from(versions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_index_lines() {
+        let contents = r#"{"name":"foo","vers":"0.1.0","deps":[],"cksum":"abc","features":{},"yanked":false}
+
+{"name":"foo","vers":"0.2.0-rc.1","deps":[],"cksum":"def","features":{},"yanked":true,"rust_version":"1.60"}
+"#;
+        let versions = parse(contents, false).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0], "0.1.0");
+        assert!(!versions[0].yanked);
+        assert_eq!(versions[1], "0.2.0-rc.1");
+        assert!(versions[1].yanked);
+        assert!(versions[1].created_at.is_none());
+        assert_eq!(versions[1].rust_version, Some(SemVer::new(1, 60, 0)));
+    }
+
+    #[test]
+    fn reports_bad_line() {
+        let contents = "{\"vers\":\"0.1.0\",\"yanked\":false}\nnot json\n";
+        let error = parse(contents, false).unwrap_err();
+        assert_eq!(error.to_string(), "Couldn't parse index line 2");
+    }
+
+    #[test]
+    fn parses_lenient_versions() {
+        let contents = "{\"vers\":\"v1.02.0\",\"yanked\":false}\n";
+        assert!(parse(contents, false).is_err());
+        let versions = parse(contents, true).unwrap();
+        assert_eq!(versions[0], "1.2.0");
+    }
+}
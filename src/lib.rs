@@ -48,38 +48,560 @@
 //! default-features = false # If you want async, you probably don't want blocking
 //! features = ["async"]
 //! ```
+//!
+//! ## `ffi`
+//!
+//! Provides a minimal C ABI in the `ffi` module, for checking versions from
+//! other languages. Enables `blocking`.
+//!
+//! ## `test-util`
+//!
+//! Provides a local server in the `test_util` module that responds with
+//! versions you choose, so you can test your own update checks without
+//! making requests to Crates.io. Use it with `FetchConfig::base_url`.
+//!
+//! ## `simd-json`
+//!
+//! Parses responses with [`simd-json`] instead of `serde_json`, which can be
+//! faster for crates with many versions. This is used by `Versions::from_json`
+//! and when fetching versions, except when versions are parsed leniently.
+//! `Versions::from_reader` and `Versions::async_from_reader` always use
+//! `serde_json`.
+//!
+//! [`simd-json`]: https://docs.rs/simd-json
+//!
+//! ## `tracing`
+//!
+//! Emits [`tracing`] events when versions are fetched, with the URL, the HTTP
+//! status, the size of the body, and how long it took to parse. This is
+//! useful for finding out why a check failed. Without this feature, nothing
+//! is recorded.
+//!
+//! [`tracing`]: https://docs.rs/tracing
+//!
+//! ## Neither
+//!
+//! With both features disabled, no requests can be made, and `reqwest` and
+//! `tokio` aren't dependencies, which is useful for targets they don't
+//! support, like WebAssembly in a browser. `Versions` and `Version`, and all
+//! of their methods that don't make requests, like the `max_*` methods, are
+//! still available, as are `FetchConfig` and `build_url`. You can make the
+//! request with your own HTTP client, using `build_url`, and parse the
+//! response with `Versions::from_json` or `Versions::from_reader`.
+//!
+//! The `check*!` macros, `FetchConfig::add_root_certificate`, and the
+//! `blocking` and `async` modules are *not* available.
+//!
+//! ```toml
+//! [dependencies.check-latest]
+//! default-features = false
+//! ```
 
 #![deny(missing_docs)]
 
-use chrono::{DateTime, Utc};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use once_cell::sync::OnceCell;
 use semver::Version as SemVer;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// A collection of `Version`s.
+///
+/// # Choosing a version
+///
+/// The *max* methods choose by SemVer version, and the *newest* methods
+/// choose by publish date. Which versions they choose from is:
+///
+/// | Versions                 | Max by SemVer                 | Newest by publish date           |
+/// |--------------------------|-------------------------------|----------------------------------|
+/// | Any                      | `max_version`                 | `newest_version`                 |
+/// | Not yanked               | `max_unyanked_version`        | `newest_unyanked_version`        |
+/// | Yanked                   | `max_yanked_version`          | `newest_yanked_version`          |
+/// | Stable                   | `max_stable_version`          | `newest_stable_version`          |
+/// | Any, matching a req      | `max_version_in_req`          | `newest_version_in_req`          |
+/// | Not yanked, matching one | `max_unyanked_version_in_req` | `newest_unyanked_version_in_req` |
+///
+/// A stable version is one that hasn't been yanked and isn't a pre-release.
+/// The newest methods ignore versions without a publish date.
 #[derive(Debug, Deserialize)]
 pub struct Versions {
     versions: Vec<Version>,
+    #[serde(rename = "crate", default)]
+    crate_meta: Option<CrateMeta>,
+    #[serde(skip)]
+    source_url: Option<String>,
+    /// The index of the max version that hasn't been yanked, once it has been
+    /// found
+    #[serde(skip)]
+    max_unyanked: OnceCell<Option<usize>>,
+}
+
+/// Information about the crate itself, as reported by the registry, which
+/// stays correct when the list of versions is changed.
+#[derive(Clone, Debug, Default, Deserialize)]
+struct CrateMeta {
+    /// How many times any version of the crate has been downloaded
+    downloads: Option<u64>,
+    /// The version that Cargo would choose by default, like with `cargo add`
+    default_version: Option<SemVer>,
+    /// The URL of the crate's source repository
+    repository: Option<String>,
+    /// The URL of the crate's home page
+    homepage: Option<String>,
+    /// The URL of the crate's documentation
+    documentation: Option<String>,
 }
 
 /// A release to [Crates.io].
 ///
+/// Two `Version`s are equal, and hash the same, if their SemVer versions are
+/// equal. Whether they were yanked and when they were published are *not*
+/// compared, so a `Version` can be used to deduplicate releases in a
+/// `HashSet` or as a key in a `HashMap`.
+///
 /// [Crates.io]: https://crates.io/
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[non_exhaustive]
 pub struct Version {
     #[serde(rename = "num")]
     version: SemVer,
     /// If this version was yanked
     pub yanked: bool,
-    /// When this version was published
-    pub created_at: DateTime<Utc>,
+    /// When this version was published, if the registry reported it
+    pub created_at: Option<DateTime<Utc>>,
+    /// How many times this version has been downloaded, if the registry
+    /// reported it
+    pub downloads: Option<u64>,
+    /// The minimum supported Rust version, if one was declared and the
+    /// registry reported it
+    #[serde(default, deserialize_with = "deserialize_rust_version")]
+    pub rust_version: Option<SemVer>,
+    #[serde(default)]
+    yank_message: Option<String>,
+}
+
+/// How significant the change from one version to another is.
+///
+/// Ordered from least to most significant, so `BumpKind::Patch <
+/// BumpKind::Minor < BumpKind::Major`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BumpKind {
+    /// Only the PATCH version, or only the pre-release, changed
+    Patch,
+    /// The MINOR version changed
+    Minor,
+    /// The MAJOR version changed
+    Major,
+}
+
+/// The result of checking for an update.
+///
+/// Created with [`Versions::check_update`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UpdateCheck {
+    /// The current version is the max version
+    UpToDate,
+    /// The current version is greater than any version that was published,
+    /// like a development build
+    Ahead,
+    /// A greater version is available
+    UpdateAvailable(Version),
+}
+
+/// Why there is no update to recommend.
+///
+/// Created with [`Versions::explain_no_update`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NoUpdateReason {
+    /// No version is newer than the current version
+    UpToDate,
+    /// Newer versions exist, but all of them have been yanked
+    OnlyYanked,
+    /// Newer versions exist, but the ones that haven't been yanked are all
+    /// pre-releases
+    OnlyPrerelease,
+}
+
+/// The differences between two fetches of the same crate's versions.
+///
+/// Created with [`Versions::diff`].
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct VersionsDiff {
+    /// Versions that were not in the older list
+    pub added: Vec<Version>,
+    /// Versions that were not yanked in the older list, but are now yanked
+    pub newly_yanked: Vec<Version>,
+}
+
+/// The differences between two lists of the same crate's versions, from
+/// different sources.
+///
+/// Created with [`Versions::reconcile`].
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ReconcileReport {
+    /// Versions that are only in the first list
+    pub only_in_self: Vec<Version>,
+    /// Versions that are only in the other list
+    pub only_in_other: Vec<Version>,
+    /// Versions that are yanked in one list but not the other, as they are in
+    /// the first list
+    pub yank_mismatches: Vec<Version>,
+}
+
+impl ReconcileReport {
+    /// Checks if both lists agree.
+    pub fn is_consistent(&self) -> bool {
+        self.only_in_self.is_empty()
+            && self.only_in_other.is_empty()
+            && self.yank_mismatches.is_empty()
+    }
+}
+
+/// A summary of a crate's releases, for dependency dashboards.
+///
+/// Created with [`Versions::health`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct HealthReport {
+    /// How many versions there are
+    pub total: usize,
+    /// How many versions have been yanked
+    pub yanked_count: usize,
+    /// The max version that hasn't been yanked
+    pub latest: Option<Version>,
+    /// How many days ago the newest version was published
+    pub days_since_last_release: Option<i64>,
+    /// The fraction of versions that have been yanked, from `0.0` to `1.0`
+    pub yank_ratio: f64,
+}
+
+/// The update to recommend, and whether a newer version was yanked.
+///
+/// Created with [`Versions::recommended_update`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RecommendedUpdate<'a> {
+    /// The max version that is greater than the current version and hasn't
+    /// been yanked
+    pub recommend: Option<&'a Version>,
+    /// If a yanked version is greater than both the current version and the
+    /// recommended version
+    pub newer_yanked_exists: bool,
+}
+
+/// How to write a version requirement for `Cargo.toml`.
+///
+/// Used by [`Version::suggest_requirement`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReqStyle {
+    /// The full version, like `"1.5.2"`, which Cargo treats as `^1.5.2`
+    Caret,
+    /// Exactly the version, like `"=1.5.2"`
+    Exact,
+    /// Only the MAJOR and MINOR versions, like `"1.5"`
+    MajorMinor,
+}
+
+/// Which versions to choose from.
+///
+/// Used by [`Versions::install_target`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Channel {
+    /// Versions that haven't been yanked and aren't pre-releases
+    Stable,
+    /// Versions that haven't been yanked, including pre-releases
+    Prerelease,
+    /// All versions, including yanked versions and pre-releases
+    Any,
+    /// Pre-releases with this name that haven't been yanked, like `"rc"` for
+    /// `2.0.0-rc.1`
+    ///
+    /// The name is compared to the first identifier of the pre-release.
+    Named(String),
+}
+
+/// What an installer needs to install a version, like with
+/// `cargo install my-cool-crate@1.0.0`.
+///
+/// Created with [`Versions::install_target`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct InstallTarget {
+    /// The exact version, like `"1.0.0"`
+    pub version: String,
+    /// If the version has been yanked
+    pub yanked: bool,
+}
+
+/// A summary of a crate's latest versions.
+///
+/// Created from a `Versions` with `TryFrom`, which fails if every version has
+/// been yanked.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use check_latest::{LatestInfo, Versions};
+/// use std::convert::TryInto;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")?;
+/// let latest: LatestInfo = (&versions).try_into()?;
+/// println!("The latest version is {}", latest.max_version);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct LatestInfo {
+    /// The max version that hasn't been yanked
+    pub max_version: Version,
+    /// The max stable version, if there is one
+    pub max_stable_version: Option<Version>,
+}
+
+/// Parses a `rust-version` like `"1.60"` into a full SemVer version like
+/// `1.60.0`.
+///
+/// Returns `None` if `rust_version` isn't a valid `rust-version`.
+pub(crate) fn parse_rust_version(rust_version: &str) -> Option<SemVer> {
+    let parts: Vec<&str> = rust_version.trim().split('.').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let mut numbers = [0; 3];
+    for (number, part) in numbers.iter_mut().zip(&parts) {
+        *number = part.parse().ok()?;
+    }
+    Some(SemVer::new(numbers[0], numbers[1], numbers[2]))
+}
+
+/// Parses a version like `semver` does, but also accepts leading and trailing
+/// whitespace, a leading `v`, and leading zeros in the MAJOR, MINOR, and PATCH
+/// versions.
+///
+/// This is useful for versions that come from users, like `v1.2.3`, which
+/// would otherwise fail to compare with a `Version`.
+///
+/// # Example
+///
+/// ```rust
+/// use check_latest::parse_version_lenient;
+///
+/// let version = parse_version_lenient(" v1.2.3\n").unwrap();
+/// assert_eq!(version, semver::Version::new(1, 2, 3));
+/// assert!(parse_version_lenient("1.2").is_err());
+/// ```
+pub fn parse_version_lenient(version: &str) -> Result<SemVer> {
+    try_parse_version_lenient(version)
+        .ok_or_else(|| anyhow!("Couldn't parse version {:?}, even leniently", version))
+}
+
+/// Like `parse_version_lenient`, but returns `None` if `version` isn't a valid
+/// version.
+fn try_parse_version_lenient(version: &str) -> Option<SemVer> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    let core_len = version.find(['-', '+']).unwrap_or(version.len());
+    let (core, rest) = version.split_at(core_len);
+    let parts: Vec<&str> = core.split('.').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let mut numbers = [0u64; 3];
+    for (number, part) in numbers.iter_mut().zip(&parts) {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *number = part.parse().ok()?;
+    }
+    let normalized = format!("{}.{}.{}{}", numbers[0], numbers[1], numbers[2], rest);
+    SemVer::parse(&normalized).ok()
+}
+
+/// Orders versions by their SemVer version, for choosing the max version.
+///
+/// If the same version is in the list more than once, like when lists from
+/// different sources are combined, one that hasn't been yanked is ordered
+/// higher, so that it is chosen. If that's still a tie, the one that was
+/// published later is ordered higher, and one without a publish date is
+/// ordered lowest.
+fn cmp_for_max(v1: &Version, v2: &Version) -> Ordering {
+    v1.version
+        .cmp(&v2.version)
+        .then_with(|| v2.yanked.cmp(&v1.yanked))
+        .then_with(|| v1.created_at.cmp(&v2.created_at))
+}
+
+/// Links to `CHANGELOG.md` in `repository`, if it's on a host with a known URL
+/// scheme for files.
+fn changelog_in_repository(repository: &str) -> Option<String> {
+    let repository = repository.trim_end_matches('/');
+    let repository = repository.strip_suffix(".git").unwrap_or(repository);
+    let path = repository
+        .strip_prefix("https://")
+        .or_else(|| repository.strip_prefix("http://"))?;
+    let path = path.strip_prefix("www.").unwrap_or(path);
+    if path.starts_with("github.com/") {
+        Some(format!("{}/blob/HEAD/CHANGELOG.md", repository))
+    } else if path.starts_with("gitlab.com/") {
+        Some(format!("{}/-/blob/HEAD/CHANGELOG.md", repository))
+    } else {
+        None
+    }
+}
+
+/// Replaces a JSON version string with its normalized form, if it can be
+/// parsed leniently.
+fn normalize_version(version: Option<&mut Value>) {
+    if let Some(version) = version {
+        if let Some(parsed) = version.as_str().and_then(try_parse_version_lenient) {
+            *version = Value::String(parsed.to_string());
+        }
+    }
+}
+
+/// Parses a publish date as RFC 3339, like `2020-01-01T00:00:00Z`, or, if
+/// that fails, with a space instead of the `T`, or without a time zone, which
+/// is assumed to be UTC.
+pub(crate) fn parse_timestamp_lenient(timestamp: &str) -> Option<DateTime<Utc>> {
+    let timestamp = timestamp.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(timestamp) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if timestamp.get(10..11) == Some(" ") {
+        let with_t = format!("{}T{}", &timestamp[..10], &timestamp[11..]);
+        if let Ok(parsed) = DateTime::parse_from_rfc3339(&with_t) {
+            return Some(parsed.with_timezone(&Utc));
+        }
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(timestamp, format).ok())
+        .map(|naive| Utc.from_utc_datetime(&naive))
+}
+
+/// Replaces a JSON publish date with its RFC 3339 form, if it can be parsed
+/// leniently, or with `null` if it can't be parsed at all.
+#[cfg_attr(not(any(feature = "async", feature = "blocking")), allow(dead_code))]
+fn normalize_timestamp(timestamp: Option<&mut Value>) {
+    if let Some(timestamp) = timestamp {
+        if let Some(raw) = timestamp.as_str() {
+            *timestamp = parse_timestamp_lenient(raw)
+                .and_then(|parsed| serde_json::to_value(parsed).ok())
+                .unwrap_or(Value::Null);
+        }
+    }
+}
+
+/// Deserializes a `rust-version`, treating one that can't be parsed as
+/// unknown.
+fn deserialize_rust_version<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<SemVer>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let rust_version: Option<String> = Option::deserialize(deserializer)?;
+    Ok(rust_version.as_deref().and_then(parse_rust_version))
 }
 
 impl Versions {
+    /// Parses versions from the body of a response from the [Crates.io] API.
+    ///
+    /// This is useful if you've made the request yourself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use check_latest::Versions;
+    ///
+    /// let body = r#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+    /// let versions = Versions::from_json(body).unwrap();
+    /// assert_eq!(versions.max_version().unwrap(), &"1.0.0");
+    /// ```
+    ///
+    /// [Crates.io]: https://crates.io/
+    pub fn from_json(json: &str) -> Result<Versions> {
+        #[cfg(feature = "simd-json")]
+        {
+            let mut json = json.as_bytes().to_vec();
+            simd_json::serde::from_slice(&mut json).context("Couldn't read as JSON")
+        }
+        #[cfg(not(feature = "simd-json"))]
+        serde_json::from_str(json).context("Couldn't read as JSON")
+    }
+    /// Like `from_json`, but reads the body from `reader`, without reading it
+    /// into a `String` first.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::from_reader(std::io::stdin()).unwrap();
+    /// ```
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Versions> {
+        serde_json::from_reader(reader).context("Couldn't read as JSON")
+    }
+    /// Like `from_json`, but if `lenient` is set, versions that aren't valid
+    /// SemVer are parsed with [`parse_version_lenient`], and publish dates
+    /// with [`parse_timestamp_lenient`].
+    #[cfg_attr(not(any(feature = "async", feature = "blocking")), allow(dead_code))]
+    pub(crate) fn from_json_with(json: &str, lenient: bool) -> Result<Versions> {
+        if !lenient {
+            return Versions::from_json(json);
+        }
+        let mut value: Value = serde_json::from_str(json).context("Couldn't read as JSON")?;
+        if let Some(versions) = value.get_mut("versions").and_then(Value::as_array_mut) {
+            for version in versions {
+                normalize_version(version.get_mut("num"));
+                normalize_timestamp(version.get_mut("created_at"));
+            }
+        }
+        if let Some(meta) = value.get_mut("crate") {
+            normalize_version(meta.get_mut("default_version"));
+        }
+        serde_json::from_value(value).context("Couldn't read as JSON")
+    }
+    /// Parses the `body` that was fetched from `url`, and remembers `url` as
+    /// the source.
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub(crate) fn from_fetched(url: String, body: &str, config: &FetchConfig) -> Result<Versions> {
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+        let mut versions = Versions::from_json_with(body, config.is_lenient())?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            url = %url,
+            body_size = body.len(),
+            versions = versions.versions.len(),
+            parse_duration = ?start.elapsed(),
+            "Parsed crate versions",
+        );
+        versions.source_url = Some(url);
+        Ok(versions)
+    }
     /// Gets *any* max version.
     ///
+    /// If the max version is in the list more than once, and only some of
+    /// them have been yanked, one that hasn't been yanked is returned. This
+    /// applies to all of the `max_*` methods. Any remaining tie is broken by
+    /// returning the one that was published last, so the result doesn't
+    /// depend on the order of the list.
+    ///
     /// # Example
     ///
     /// ```rust,no_run
@@ -90,12 +612,39 @@ impl Versions {
     ///     .max_version();
     /// ```
     pub fn max_version(&self) -> Option<&Version> {
-        self.versions
-            .iter()
-            .max_by(|v1, v2| v1.version.cmp(&v2.version))
+        self.versions.iter().max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets the max version, using `cmp` to compare versions.
+    ///
+    /// This is useful if none of the other `max_*` methods order versions
+    /// the way you need. If several versions are equally max, the last one is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// // Prefer versions that haven't been yanked, then the newest version.
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_version_with(|v1, v2| {
+    ///         v2.yanked
+    ///             .cmp(&v1.yanked)
+    ///             .then_with(|| v1.created_at.cmp(&v2.created_at))
+    ///     });
+    /// ```
+    pub fn max_version_with<F>(&self, cmp: F) -> Option<&Version>
+    where
+        F: Fn(&Version, &Version) -> Ordering,
+    {
+        self.versions.iter().max_by(|v1, v2| cmp(v1, v2))
     }
     /// Gets the max version that hasn't been yanked.
     ///
+    /// The result is remembered, so calling this again is cheap, until the
+    /// list is changed with `versions_mut`.
+    ///
     /// # Example
     ///
     /// ```rust,no_run
@@ -106,10 +655,68 @@ impl Versions {
     ///     .max_unyanked_version();
     /// ```
     pub fn max_unyanked_version(&self) -> Option<&Version> {
+        let index = self
+            .max_unyanked
+            .get_or_init(|| self.find_max_unyanked_index());
+        index.map(|index| &self.versions[index])
+    }
+    /// Finds the index of the max version that hasn't been yanked, for
+    /// `max_unyanked_version`.
+    fn find_max_unyanked_index(&self) -> Option<usize> {
+        self.versions
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| !v.yanked)
+            .max_by(|(_, v1), (_, v2)| cmp_for_max(v1, v2))
+            .map(|(index, _)| index)
+    }
+    /// Gets the max version that hasn't been yanked, with the URL of its page
+    /// on [Crates.io].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if let Some((version, url)) = versions.latest_with_url("my-cool-crate") {
+    ///     println!("Version {} is now available! See {}", version, url);
+    /// }
+    /// ```
+    ///
+    /// [Crates.io]: https://crates.io/
+    pub fn latest_with_url(&self, crate_name: &str) -> Option<(&Version, String)> {
+        let latest = self.max_unyanked_version()?;
+        Some((latest, latest.crates_io_url(crate_name)))
+    }
+    /// Gets the max version that hasn't been yanked and isn't in `deny`.
+    ///
+    /// This is useful to enforce a policy of never recommending certain
+    /// versions, like ones with known vulnerabilities. See `load_denylist` to
+    /// read `deny` from a file.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::Version;
+    /// use std::collections::HashSet;
+    ///
+    /// let deny: HashSet<Version> = [Version::parse("1.2.3").unwrap()].into_iter().collect();
+    /// let allowed = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_unyanked_version_except(&deny);
+    /// ```
+    pub fn max_unyanked_version_except(&self, deny: &HashSet<SemVer>) -> Option<&Version> {
         self.versions
             .iter()
             .filter(|v| !v.yanked)
-            .max_by(|v1, v2| v1.version.cmp(&v2.version))
+            .filter(|v| !deny.contains(&v.version))
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Like `max_unyanked_version`, but returns an owned clone.
+    pub fn max_unyanked_version_owned(&self) -> Option<Version> {
+        self.max_unyanked_version().cloned()
     }
     /// Gets max version that has been yanked.
     ///
@@ -126,31 +733,29 @@ impl Versions {
         self.versions
             .iter()
             .filter(|v| v.yanked)
-            .max_by(|v1, v2| v1.version.cmp(&v2.version))
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
     }
-    /// Gets *any* max version with the same major version.
-    ///
-    /// For example, if `major` = 1, then `1.0.0 <= max_minor_version < 2.0.0`.
+    /// Gets the min version that hasn't been yanked.
     ///
     /// # Example
     ///
     /// ```rust,no_run
     /// use check_latest::Versions;
     ///
-    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    /// let oldest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
     ///     .unwrap()
-    ///     .max_minor_version(1);
+    ///     .oldest_unyanked_version();
     /// ```
-    pub fn max_minor_version(&self, major: u64) -> Option<&Version> {
+    pub fn oldest_unyanked_version(&self) -> Option<&Version> {
         self.versions
             .iter()
-            .filter(|v| v.major() == major)
-            .max_by(|v1, v2| v1.version.cmp(&v2.version))
+            .filter(|v| !v.yanked)
+            .min_by(|v1, v2| v1.version.cmp(&v2.version))
     }
-    /// Gets the max version that hasn't been yanked with the same major
-    /// version.
+    /// Gets the max stable version.
     ///
-    /// For example, if `major` = 1, then `1.0.0 <= max_minor_version < 2.0.0`.
+    /// A stable version is one that hasn't been yanked and isn't a
+    /// pre-release.
     ///
     /// # Example
     ///
@@ -159,136 +764,163 @@ impl Versions {
     ///
     /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
     ///     .unwrap()
-    ///     .max_unyanked_minor_version(1);
+    ///     .max_stable_version();
     /// ```
-    pub fn max_unyanked_minor_version(&self, major: u64) -> Option<&Version> {
+    pub fn max_stable_version(&self) -> Option<&Version> {
         self.versions
             .iter()
-            .filter(|v| !v.yanked)
-            .filter(|v| v.major() == major)
-            .max_by(|v1, v2| v1.version.cmp(&v2.version))
+            .filter(|v| v.is_stable())
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
     }
-    /// Gets max version that has been yanked with the same major version.
+    /// Checks if any version that hasn't been yanked is newer than `current`.
     ///
-    /// For example, if `major` = 1, then `1.0.0 <= max_minor_version < 2.0.0`.
+    /// This stops at the first newer version, so it's faster than finding
+    /// the max version if only a yes or no is needed.
     ///
     /// # Example
     ///
     /// ```rust,no_run
     /// use check_latest::Versions;
     ///
-    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    /// let current = semver::Version::parse("1.0.0").unwrap();
+    /// if Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
     ///     .unwrap()
-    ///     .max_yanked_minor_version(1);
+    ///     .any_newer(&current)
+    /// {
+    ///     println!("An update is available");
+    /// }
     /// ```
-    pub fn max_yanked_minor_version(&self, major: u64) -> Option<&Version> {
+    pub fn any_newer(&self, current: &SemVer) -> bool {
         self.versions
             .iter()
-            .filter(|v| v.yanked)
-            .filter(|v| v.major() == major)
-            .max_by(|v1, v2| v1.version.cmp(&v2.version))
+            .any(|v| !v.yanked && v.version > *current)
     }
-    /// Gets *any* max version with the same major and minor version.
-    ///
-    /// For example, if `major` = 1 and `minor` = 2,
-    /// then `1.2.0 <= max_patch < 1.3.0`.
+    /// Like `any_newer`, but parses `current` first.
     ///
     /// # Example
     ///
     /// ```rust,no_run
     /// use check_latest::Versions;
     ///
-    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
-    ///     .unwrap()
-    ///     .max_patch(1, 2);
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if versions.any_newer_str("1.0.0").unwrap() {
+    ///     println!("An update is available");
+    /// }
     /// ```
-    pub fn max_patch(&self, major: u64, minor: u64) -> Option<&Version> {
-        self.versions
-            .iter()
-            .filter(|v| v.major() == major)
-            .filter(|v| v.minor() == minor)
-            .max_by(|v1, v2| v1.version.cmp(&v2.version))
+    pub fn any_newer_str(&self, current: &str) -> Result<bool> {
+        let current =
+            SemVer::parse(current).map_err(|e| anyhow!("Couldn't parse version: {}", e))?;
+        Ok(self.any_newer(&current))
     }
-    /// Gets the max version that hasn't been yanked with the same major
-    /// and minor version.
+    /// Gets the best upgrade from `current`.
     ///
-    /// For example, if `major` = 1 and `minor` = 2,
-    /// then `1.2.0 <= max_patch < 1.3.0`.
+    /// This is the max version that hasn't been yanked and is newer than
+    /// `current`. Pre-releases are only considered if `current` is a
+    /// pre-release too, so that users who opted into pre-releases keep
+    /// getting them, and users on a stable version stay on stable versions.
     ///
     /// # Example
     ///
     /// ```rust,no_run
     /// use check_latest::Versions;
     ///
-    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    /// let current = semver::Version::parse("1.0.0-rc.1").unwrap();
+    /// if let Some(version) = Versions::new("my-cool-crate", "my-cool-crate/1.0.0-rc.1")
     ///     .unwrap()
-    ///     .max_unyanked_patch(1, 2);
+    ///     .best_upgrade(&current)
+    /// {
+    ///     println!("Upgrade to {}", version);
+    /// }
     /// ```
-    pub fn max_unyanked_patch(&self, major: u64, minor: u64) -> Option<&Version> {
+    pub fn best_upgrade(&self, current: &SemVer) -> Option<&Version> {
+        let allow_prerelease = !current.pre.is_empty();
         self.versions
             .iter()
             .filter(|v| !v.yanked)
-            .filter(|v| v.major() == major)
-            .filter(|v| v.minor() == minor)
-            .max_by(|v1, v2| v1.version.cmp(&v2.version))
+            .filter(|v| allow_prerelease || !v.is_prerelease())
+            .filter(|v| v.version > *current)
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
     }
-    /// Gets max version that has been yanked with the same major and minor
-    /// version.
+    /// Gets the version that should be recommended as an upgrade.
     ///
-    /// For example, if `major` = 1 and `minor` = 2,
-    /// then `1.2.0 <= max_patch < 1.3.0`.
+    /// This is the max version that hasn't been yanked and isn't a
+    /// pre-release, which is what `cargo add` installs by default. It's the
+    /// same as `max_stable_version`.
     ///
     /// # Example
     ///
     /// ```rust,no_run
     /// use check_latest::Versions;
     ///
-    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    /// if let Some(version) = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
     ///     .unwrap()
-    ///     .max_yanked_patch(1, 2);
+    ///     .recommended()
+    /// {
+    ///     println!("Upgrade to {}", version);
+    /// }
     /// ```
-    pub fn max_yanked_patch(&self, major: u64, minor: u64) -> Option<&Version> {
-        self.versions
-            .iter()
-            .filter(|v| v.yanked)
-            .filter(|v| v.major() == major)
-            .filter(|v| v.minor() == minor)
-            .max_by(|v1, v2| v1.version.cmp(&v2.version))
+    pub fn recommended(&self) -> Option<&Version> {
+        self.max_stable_version()
     }
-    /// Gets *any* newest version.
+    /// Gets the max stable version that was published at least `min_age` ago.
+    ///
+    /// This is useful for waiting a while before recommending an update, in
+    /// case the new version is quickly yanked. Versions without a publish
+    /// date are ignored.
     ///
     /// # Example
     ///
     /// ```rust,no_run
     /// use check_latest::Versions;
     ///
-    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    /// let settled = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
     ///     .unwrap()
-    ///     .newest_version();
+    ///     .max_stable_version_aged(chrono::Duration::hours(48));
     /// ```
-    pub fn newest_version(&self) -> Option<&Version> {
+    pub fn max_stable_version_aged(&self, min_age: Duration) -> Option<&Version> {
+        self.max_stable_version_aged_at(min_age, now())
+    }
+    fn max_stable_version_aged_at(
+        &self,
+        min_age: Duration,
+        now: DateTime<Utc>,
+    ) -> Option<&Version> {
         self.versions
             .iter()
-            .max_by(|v1, v2| v1.created_at.cmp(&v2.created_at))
+            .filter(|v| v.is_stable())
+            .filter(|v| match v.created_at {
+                Some(created_at) => now.signed_duration_since(created_at) >= min_age,
+                None => false,
+            })
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
     }
-    /// Gets the newest version that hasn't been yanked.
+    /// Gets the version that the registry reported as the default, which is
+    /// the version that `cargo add` would choose.
+    ///
+    /// Returns `None` if the registry didn't report a default version, or the
+    /// reported version isn't in the list. The reported version is kept when
+    /// the list is changed with `versions_mut` or `filter`, so it is returned
+    /// as long as it is still in the list.
     ///
     /// # Example
     ///
     /// ```rust,no_run
     /// use check_latest::Versions;
     ///
-    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    /// let default = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
     ///     .unwrap()
-    ///     .newest_unyanked_version();
+    ///     .default_version();
     /// ```
-    pub fn newest_unyanked_version(&self) -> Option<&Version> {
+    pub fn default_version(&self) -> Option<&Version> {
+        let default = self.crate_meta.as_ref()?.default_version.as_ref()?;
         self.versions
             .iter()
-            .filter(|v| !v.yanked)
-            .max_by(|v1, v2| v1.created_at.cmp(&v2.created_at))
+            .filter(|v| v.version == *default)
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
     }
-    /// Gets newest version that has been yanked.
+    /// Gets *any* max version with the same major version.
+    ///
+    /// For example, if `major` = 1, then `1.0.0 <= max_minor_version < 2.0.0`.
     ///
     /// # Example
     ///
@@ -297,234 +929,3271 @@ impl Versions {
     ///
     /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
     ///     .unwrap()
-    ///     .newest_yanked_version();
+    ///     .max_minor_version(1);
     /// ```
-    pub fn newest_yanked_version(&self) -> Option<&Version> {
+    pub fn max_minor_version(&self, major: u64) -> Option<&Version> {
         self.versions
             .iter()
-            .filter(|v| v.yanked)
-            .max_by(|v1, v2| v1.created_at.cmp(&v2.created_at))
+            .filter(|v| v.major() == major)
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
     }
-    /// Gets the full list of versions that were found.
-    pub fn versions(&self) -> &Vec<Version> {
-        &self.versions
+    /// Gets the max version that hasn't been yanked with the same major
+    /// version.
+    ///
+    /// For example, if `major` = 1, then `1.0.0 <= max_minor_version < 2.0.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_unyanked_minor_version(1);
+    /// ```
+    pub fn max_unyanked_minor_version(&self, major: u64) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter(|v| v.major() == major)
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets the max version that hasn't been yanked with the same major
+    /// version as `current`, if it's newer than `current`.
+    ///
+    /// This is like `max_unyanked_minor_version(current.major)`, but only
+    /// returns an update.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let current = semver::Version::parse("1.2.3").unwrap();
+    /// let update = Versions::new("my-cool-crate", "my-cool-crate/1.2.3")
+    ///     .unwrap()
+    ///     .max_unyanked_minor_for(&current);
+    /// ```
+    pub fn max_unyanked_minor_for(&self, current: &SemVer) -> Option<&Version> {
+        self.max_unyanked_minor_version(current.major)
+            .filter(|v| v.version > *current)
+    }
+    /// Like `max_unyanked_minor_version`, but returns an owned clone.
+    pub fn max_unyanked_minor_version_owned(&self, major: u64) -> Option<Version> {
+        self.max_unyanked_minor_version(major).cloned()
+    }
+    /// Gets max version that has been yanked with the same major version.
+    ///
+    /// For example, if `major` = 1, then `1.0.0 <= max_minor_version < 2.0.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_yanked_minor_version(1);
+    /// ```
+    pub fn max_yanked_minor_version(&self, major: u64) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| v.yanked)
+            .filter(|v| v.major() == major)
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets *any* max version with the same major and minor version.
+    ///
+    /// For example, if `major` = 1 and `minor` = 2,
+    /// then `1.2.0 <= max_patch < 1.3.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_patch(1, 2);
+    /// ```
+    pub fn max_patch(&self, major: u64, minor: u64) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| v.major() == major)
+            .filter(|v| v.minor() == minor)
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets the max version that hasn't been yanked with the same major
+    /// and minor version.
+    ///
+    /// For example, if `major` = 1 and `minor` = 2,
+    /// then `1.2.0 <= max_patch < 1.3.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_unyanked_patch(1, 2);
+    /// ```
+    pub fn max_unyanked_patch(&self, major: u64, minor: u64) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter(|v| v.major() == major)
+            .filter(|v| v.minor() == minor)
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Like `max_unyanked_patch`, but returns an owned clone.
+    pub fn max_unyanked_patch_owned(&self, major: u64, minor: u64) -> Option<Version> {
+        self.max_unyanked_patch(major, minor).cloned()
+    }
+    /// Gets max version that has been yanked with the same major and minor
+    /// version.
+    ///
+    /// For example, if `major` = 1 and `minor` = 2,
+    /// then `1.2.0 <= max_patch < 1.3.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_yanked_patch(1, 2);
+    /// ```
+    pub fn max_yanked_patch(&self, major: u64, minor: u64) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| v.yanked)
+            .filter(|v| v.major() == major)
+            .filter(|v| v.minor() == minor)
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets *any* max version between `floor` and `ceiling`.
+    ///
+    /// If `inclusive` is `true`, then `floor <= max_version_in_range <= ceiling`.
+    /// Otherwise, both bounds are excluded, and
+    /// `floor < max_version_in_range < ceiling`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::Version;
+    ///
+    /// let floor = Version::parse("1.2.0").unwrap();
+    /// let ceiling = Version::parse("1.5.0").unwrap();
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_version_in_range(&floor, &ceiling, false);
+    /// ```
+    pub fn max_version_in_range(
+        &self,
+        floor: &SemVer,
+        ceiling: &SemVer,
+        inclusive: bool,
+    ) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| {
+                if inclusive {
+                    floor <= &v.version && &v.version <= ceiling
+                } else {
+                    floor < &v.version && &v.version < ceiling
+                }
+            })
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets the max version that hasn't been yanked and is an update of at
+    /// least `min_bump` from `current`.
+    ///
+    /// For example, with a `min_bump` of `BumpKind::Minor`, an update from
+    /// `1.2.3` to `1.2.4` will be ignored, but an update from `1.2.3` to
+    /// `1.3.0` will not. A `min_bump` of `BumpKind::Patch` accepts any
+    /// update, including pre-releases, which is the same behavior as
+    /// `check_max!(include_prerelease = true)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{BumpKind, Versions};
+    /// use semver::Version;
+    ///
+    /// let current = Version::parse("1.0.0").unwrap();
+    /// let update = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_update(&current, BumpKind::Minor);
+    /// ```
+    pub fn max_update(&self, current: &SemVer, min_bump: BumpKind) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter(|v| {
+                v.bump_kind_from(current)
+                    .map_or(false, |bump| bump >= min_bump)
+            })
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets the max version that hasn't been yanked, is greater than
+    /// `current`, and can be built with the Rust `toolchain` version.
+    ///
+    /// A version that didn't declare a minimum supported Rust version is
+    /// assumed to support `toolchain`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::Version;
+    ///
+    /// let current = Version::parse("1.0.0").unwrap();
+    /// let toolchain = Version::parse("1.60.0").unwrap();
+    /// let update = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_update_within_msrv(&current, &toolchain);
+    /// ```
+    pub fn max_update_within_msrv(&self, current: &SemVer, toolchain: &SemVer) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter(|v| v.version > *current)
+            .filter(|v| {
+                v.rust_version
+                    .as_ref()
+                    .map_or(true, |msrv| msrv <= toolchain)
+            })
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets *any* newest version.
+    ///
+    /// Versions without a publish date are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .newest_version();
+    /// ```
+    pub fn newest_version(&self) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| v.created_at.is_some())
+            .max_by(|v1, v2| v1.created_at.cmp(&v2.created_at))
+    }
+    /// Gets the newest version that hasn't been yanked.
+    ///
+    /// Versions without a publish date are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .newest_unyanked_version();
+    /// ```
+    pub fn newest_unyanked_version(&self) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter(|v| v.created_at.is_some())
+            .max_by(|v1, v2| v1.created_at.cmp(&v2.created_at))
+    }
+    /// Gets newest version that has been yanked.
+    ///
+    /// Versions without a publish date are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .newest_yanked_version();
+    /// ```
+    pub fn newest_yanked_version(&self) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| v.yanked)
+            .filter(|v| v.created_at.is_some())
+            .max_by(|v1, v2| v1.created_at.cmp(&v2.created_at))
+    }
+    /// Gets both the max version and the newest version that haven't been
+    /// yanked, as `(max, newest)`.
+    ///
+    /// These differ when a version for an older line was published after the
+    /// max version, like a patch release for a previous MAJOR version.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if let (Some(max), Some(newest)) = versions.max_vs_newest() {
+    ///     if max != newest {
+    ///         println!("{} was just released for an older line", newest);
+    ///     }
+    /// }
+    /// ```
+    pub fn max_vs_newest(&self) -> (Option<&Version>, Option<&Version>) {
+        (self.max_unyanked_version(), self.newest_unyanked_version())
+    }
+    /// Gets the newest version that matches `req`.
+    ///
+    /// Yanked versions are only included if `include_yanked` is set. This is
+    /// useful for finding which version Cargo could have chosen at some point
+    /// in the past, since that version may have been yanked since. The other
+    /// methods exclude yanked versions unless their names say otherwise.
+    ///
+    /// Versions without a publish date are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::VersionReq;
+    ///
+    /// let req = VersionReq::parse("^1.2").unwrap();
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .newest_matching_req(&req, true);
+    /// ```
+    pub fn newest_matching_req(
+        &self,
+        req: &semver::VersionReq,
+        include_yanked: bool,
+    ) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| include_yanked || !v.yanked)
+            .filter(|v| req.matches(&v.version))
+            .filter(|v| v.created_at.is_some())
+            .max_by(|v1, v2| v1.created_at.cmp(&v2.created_at))
+    }
+    /// Gets *any* newest version that matches `req`.
+    ///
+    /// This is the same as `newest_matching_req(req, true)`. Versions without
+    /// a publish date are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::VersionReq;
+    ///
+    /// let req = VersionReq::parse("^1.2").unwrap();
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .newest_version_in_req(&req);
+    /// ```
+    pub fn newest_version_in_req(&self, req: &semver::VersionReq) -> Option<&Version> {
+        self.newest_matching_req(req, true)
+    }
+    /// Gets the newest version that hasn't been yanked and matches `req`.
+    ///
+    /// This is the same as `newest_matching_req(req, false)`. Versions without
+    /// a publish date are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::VersionReq;
+    ///
+    /// let req = VersionReq::parse("^1.2").unwrap();
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .newest_unyanked_version_in_req(&req);
+    /// ```
+    pub fn newest_unyanked_version_in_req(&self, req: &semver::VersionReq) -> Option<&Version> {
+        self.newest_matching_req(req, false)
+    }
+    /// Gets *any* max version that matches `req`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::VersionReq;
+    ///
+    /// let req = VersionReq::parse("^1.2").unwrap();
+    /// let max = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_version_in_req(&req);
+    /// ```
+    pub fn max_version_in_req(&self, req: &semver::VersionReq) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| req.matches(&v.version))
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets the max version that hasn't been yanked and matches `req`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::VersionReq;
+    ///
+    /// let req = VersionReq::parse("^1.2").unwrap();
+    /// let max = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_unyanked_version_in_req(&req);
+    /// ```
+    pub fn max_unyanked_version_in_req(&self, req: &semver::VersionReq) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter(|v| req.matches(&v.version))
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets the earliest published version that hasn't been yanked.
+    ///
+    /// Versions without a publish date are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let earliest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .earliest_unyanked_version();
+    /// ```
+    pub fn earliest_unyanked_version(&self) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter(|v| v.created_at.is_some())
+            .min_by(|v1, v2| v1.created_at.cmp(&v2.created_at))
+    }
+    /// Gets the newest stable version.
+    ///
+    /// A stable version is one that hasn't been yanked and isn't a
+    /// pre-release. Versions without a publish date are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .newest_stable_version();
+    /// ```
+    pub fn newest_stable_version(&self) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| v.is_stable())
+            .filter(|v| v.created_at.is_some())
+            .max_by(|v1, v2| v1.created_at.cmp(&v2.created_at))
+    }
+    /// Checks if the newest published version is a pre-release.
+    ///
+    /// This uses `newest_version`, which includes yanked versions, so a yanked
+    /// pre-release that was published last still counts. Use
+    /// `newest_unyanked_version` if yanked versions should be ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if versions.is_newest_prerelease() {
+    ///     println!("Try out the beta!");
+    /// }
+    /// ```
+    pub fn is_newest_prerelease(&self) -> bool {
+        self.newest_version()
+            .map_or(false, |newest| newest.is_prerelease())
+    }
+    /// Finds which of the `currents` versions are behind, and the update for
+    /// each of them.
+    ///
+    /// The update for each version is the max version that hasn't been
+    /// yanked, and only versions that are less than it are returned. The
+    /// versions are returned in the same order as `currents`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::Version;
+    ///
+    /// let installed = [Version::parse("1.0.0").unwrap(), Version::parse("1.2.0").unwrap()];
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// for (current, update) in versions.behind_versions(&installed) {
+    ///     println!("{} should be updated to {}", current, update);
+    /// }
+    /// ```
+    pub fn behind_versions(&self, currents: &[SemVer]) -> Vec<(SemVer, &Version)> {
+        let max = match self.max_unyanked_version() {
+            Some(max) => max,
+            None => return Vec::new(),
+        };
+        currents
+            .iter()
+            .filter(|current| max.version > **current)
+            .map(|current| (current.clone(), max))
+            .collect()
+    }
+    /// Checks if `version` has already been published.
+    ///
+    /// Yanked versions count as taken, since a registry won't accept a version
+    /// that was published before, even if it was yanked.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::Version;
+    ///
+    /// let next = Version::parse("1.1.0").unwrap();
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if versions.is_taken(&next) {
+    ///     eprintln!("{} has already been published", next);
+    /// }
+    /// ```
+    pub fn is_taken(&self, version: &SemVer) -> bool {
+        self.versions.iter().any(|v| v.version == *version)
+    }
+    /// Checks if `current` is in the list and has been yanked.
+    ///
+    /// This is useful to warn that the running version should be updated,
+    /// even if the only update is a patch.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::Version;
+    ///
+    /// let current = Version::parse("1.0.0").unwrap();
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if versions.current_is_yanked(&current) {
+    ///     println!("Your version of my-cool-crate was yanked. Please update!");
+    /// }
+    /// ```
+    pub fn current_is_yanked(&self, current: &SemVer) -> bool {
+        self.versions
+            .iter()
+            .any(|v| v.yanked && v.version == *current)
+    }
+    /// Checks if `current` is in the list and has been yanked.
+    ///
+    /// This is the same as `current_is_yanked(current)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::Version;
+    ///
+    /// let current = Version::parse("1.0.0").unwrap();
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if versions.is_current_yanked(&current) {
+    ///     println!("Your version of my-cool-crate was yanked. Please update!");
+    /// }
+    /// ```
+    pub fn is_current_yanked(&self, current: &SemVer) -> bool {
+        self.current_is_yanked(current)
+    }
+    /// Checks if there is an update from `current`.
+    ///
+    /// Updates are chosen from the versions that haven't been yanked,
+    /// including pre-releases, like `check_max!(include_prerelease = true)`.
+    /// If every version has been yanked, `current` is considered up to date.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{UpdateCheck, Versions};
+    /// use semver::Version;
+    ///
+    /// let current = Version::parse("1.0.0").unwrap();
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// match versions.check_update(&current) {
+    ///     UpdateCheck::UpdateAvailable(version) => println!("Please update to {}", version),
+    ///     UpdateCheck::Ahead => println!("You're running an unreleased version"),
+    ///     _ => println!("You're up to date"),
+    /// }
+    /// ```
+    pub fn check_update(&self, current: &SemVer) -> UpdateCheck {
+        match self.max_unyanked_version() {
+            Some(max) if max.version > *current => UpdateCheck::UpdateAvailable(max.clone()),
+            Some(max) if max.version < *current => UpdateCheck::Ahead,
+            _ => UpdateCheck::UpToDate,
+        }
+    }
+    /// Gets the update to recommend from `current`, and whether a newer
+    /// version than that was yanked.
+    ///
+    /// This is useful to recommend a version that hasn't been yanked, while
+    /// still mentioning that there was a newer release.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::Version;
+    ///
+    /// let current = Version::parse("1.0.0").unwrap();
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// let update = versions.recommended_update(&current);
+    /// if let Some(version) = update.recommend {
+    ///     println!("Please update to {}", version);
+    /// }
+    /// if update.newer_yanked_exists {
+    ///     println!("A newer version was released, but it was yanked");
+    /// }
+    /// ```
+    pub fn recommended_update(&self, current: &SemVer) -> RecommendedUpdate<'_> {
+        let recommend = self.max_unyanked_version().filter(|v| v.version > *current);
+        let floor = recommend.map_or(current, |v| &v.version);
+        let newer_yanked_exists = self.versions.iter().any(|v| v.yanked && v.version > *floor);
+        RecommendedUpdate {
+            recommend,
+            newer_yanked_exists,
+        }
+    }
+    /// Explains why there isn't a stable update from `current`.
+    ///
+    /// Returns `None` if there *is* a stable update, meaning a version newer
+    /// than `current` that hasn't been yanked and isn't a pre-release.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{NoUpdateReason, Versions};
+    /// use semver::Version;
+    ///
+    /// let current = Version::parse("1.0.0").unwrap();
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if let Some(NoUpdateReason::OnlyPrerelease) = versions.explain_no_update(&current) {
+    ///     println!("A pre-release is available, if you're feeling brave.");
+    /// }
+    /// ```
+    pub fn explain_no_update(&self, current: &SemVer) -> Option<NoUpdateReason> {
+        let mut newer = self
+            .versions
+            .iter()
+            .filter(|v| v.version > *current)
+            .peekable();
+        if newer.peek().is_none() {
+            return Some(NoUpdateReason::UpToDate);
+        }
+        let mut unyanked = newer.filter(|v| !v.yanked).peekable();
+        if unyanked.peek().is_none() {
+            return Some(NoUpdateReason::OnlyYanked);
+        }
+        if unyanked.all(|v| v.is_prerelease()) {
+            Some(NoUpdateReason::OnlyPrerelease)
+        } else {
+            None
+        }
+    }
+    /// Gets the max version in `channel`, as what an installer needs to
+    /// install it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{Channel, Versions};
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if let Some(target) = versions.install_target(Channel::Stable) {
+    ///     println!("cargo install my-cool-crate@{}", target.version);
+    /// }
+    /// ```
+    pub fn install_target(&self, channel: Channel) -> Option<InstallTarget> {
+        self.max_in_channel(&channel).map(InstallTarget::from)
+    }
+    /// Gets the max version in `channel`.
+    fn max_in_channel(&self, channel: &Channel) -> Option<&Version> {
+        match channel {
+            Channel::Stable => self.max_stable_version(),
+            Channel::Prerelease => self.max_unyanked_version(),
+            Channel::Any => self.max_version(),
+            Channel::Named(_) => self.max_for_channel(None, channel),
+        }
+    }
+    /// Gets the max version in `channel`, with the MAJOR version `major`.
+    ///
+    /// If `major` is `None`, versions with any MAJOR version are included.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{Channel, Versions};
+    ///
+    /// // The latest release candidate for 2.x
+    /// let rc = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .max_for(Some(2), Channel::Named(String::from("rc")));
+    /// ```
+    pub fn max_for(&self, major: Option<u64>, channel: Channel) -> Option<&Version> {
+        self.max_for_channel(major, &channel)
+    }
+    fn max_for_channel(&self, major: Option<u64>, channel: &Channel) -> Option<&Version> {
+        self.versions
+            .iter()
+            .filter(|v| major.map_or(true, |major| v.major() == major))
+            .filter(|v| v.in_channel(channel))
+            .max_by(|v1, v2| cmp_for_max(v1, v2))
+    }
+    /// Gets the position of `version` in the release history, starting at 1
+    /// for the first version that was published.
+    ///
+    /// Yanked versions are counted, since they were still released, and
+    /// versions published at the same time are ordered by version. Versions
+    /// without a publish date are not counted, and `None` is returned if
+    /// `version` isn't in the list or doesn't have a publish date.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use semver::Version;
+    ///
+    /// let version = Version::parse("1.0.0").unwrap();
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if let Some(ordinal) = versions.ordinal_of(&version) {
+    ///     println!("{} was release number {}", version, ordinal);
+    /// }
+    /// ```
+    pub fn ordinal_of(&self, version: &SemVer) -> Option<usize> {
+        let target = self.versions.iter().find(|v| v.version == *version)?;
+        let published = (target.created_at?, &target.version);
+        let earlier = self
+            .versions
+            .iter()
+            .filter(|v| match v.created_at {
+                Some(created_at) => (created_at, &v.version) < published,
+                None => false,
+            })
+            .count();
+        Some(earlier + 1)
+    }
+    /// Gets the most recently published version that introduced a new MAJOR
+    /// version.
+    ///
+    /// A version introduces a new MAJOR version if no version with the same
+    /// MAJOR version was published before it. Versions without a publish date
+    /// are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let last_breaking_change = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .most_recent_major_bump();
+    /// ```
+    pub fn most_recent_major_bump(&self) -> Option<&Version> {
+        let mut dated: Vec<&Version> = self
+            .versions
+            .iter()
+            .filter(|v| v.created_at.is_some())
+            .collect();
+        dated.sort_by(|v1, v2| {
+            v1.created_at
+                .cmp(&v2.created_at)
+                .then_with(|| v1.version.cmp(&v2.version))
+        });
+        let mut seen_majors = HashSet::new();
+        dated
+            .into_iter()
+            .filter(|v| seen_majors.insert(v.major()))
+            .last()
+    }
+    /// Splits the versions into those that haven't been yanked and those that
+    /// have, as `(unyanked, yanked)`.
+    ///
+    /// Both lists are sorted from lowest to highest.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// let (unyanked, yanked) = versions.partition_yanked();
+    /// println!("{} available, {} yanked", unyanked.len(), yanked.len());
+    /// ```
+    pub fn partition_yanked(&self) -> (Vec<&Version>, Vec<&Version>) {
+        let (mut yanked, mut unyanked): (Vec<&Version>, Vec<&Version>) =
+            self.versions.iter().partition(|v| v.yanked);
+        unyanked.sort_by(|v1, v2| v1.version.cmp(&v2.version));
+        yanked.sort_by(|v1, v2| v1.version.cmp(&v2.version));
+        (unyanked, yanked)
+    }
+    /// Groups every version by its MAJOR version.
+    ///
+    /// Each group is sorted from lowest to highest. Yanked versions are
+    /// included, so check `Version::yanked` if they should be treated
+    /// differently.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// for (major, versions) in versions.group_by_major() {
+    ///     println!("{}.x: {} versions", major, versions.len());
+    /// }
+    /// ```
+    pub fn group_by_major(&self) -> BTreeMap<u64, Vec<&Version>> {
+        let mut groups: BTreeMap<u64, Vec<&Version>> = BTreeMap::new();
+        for version in &self.versions {
+            groups.entry(version.major()).or_default().push(version);
+        }
+        for versions in groups.values_mut() {
+            versions.sort_by(|v1, v2| v1.version.cmp(&v2.version));
+        }
+        groups
+    }
+    /// Finds PATCH versions that appear to be missing from the published
+    /// versions.
+    ///
+    /// For each MAJOR.MINOR line, every PATCH number between the lowest and
+    /// highest published PATCH is expected. For example, if `1.0.0` and
+    /// `1.0.2` were published, then `1.0.1` is missing. Pre-releases are
+    /// ignored, and yanked versions still count as published.
+    ///
+    /// The missing versions are sorted from lowest to highest.
+    pub fn sequence_gaps(&self) -> Vec<SemVer> {
+        let mut lines: BTreeMap<(u64, u64), BTreeSet<u64>> = BTreeMap::new();
+        for version in self.versions.iter().filter(|v| !v.is_prerelease()) {
+            lines
+                .entry((version.major(), version.minor()))
+                .or_default()
+                .insert(version.patch());
+        }
+        let mut gaps = Vec::new();
+        for ((major, minor), patches) in lines {
+            let (lowest, highest) = match (patches.iter().next(), patches.iter().next_back()) {
+                (Some(&lowest), Some(&highest)) => (lowest, highest),
+                _ => continue,
+            };
+            gaps.extend(
+                (lowest..highest)
+                    .filter(|patch| !patches.contains(patch))
+                    .map(|patch| SemVer::new(major, minor, patch)),
+            );
+        }
+        gaps
+    }
+    /// Gets how many times any version of the crate has been downloaded.
+    ///
+    /// Returns `None` if the registry didn't report it.
+    pub fn total_downloads(&self) -> Option<u64> {
+        self.crate_meta.as_ref()?.downloads
+    }
+    /// Gets the fraction of versions that have been yanked, from `0.0` to
+    /// `1.0`.
+    ///
+    /// Returns `0.0` if there are no versions.
+    pub fn yank_ratio(&self) -> f64 {
+        if self.versions.is_empty() {
+            return 0.0;
+        }
+        let yanked = self.versions.iter().filter(|v| v.yanked).count();
+        yanked as f64 / self.versions.len() as f64
+    }
+    /// Gets the yanked versions that were published within `within` of now,
+    /// from newest to oldest.
+    ///
+    /// Many recently yanked versions can be a sign that a crate is unstable.
+    /// Versions without a publish date are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use chrono::Duration;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// let yanked = versions.recently_yanked(Duration::days(90));
+    /// println!("{} versions from the last 90 days were yanked", yanked.len());
+    /// ```
+    pub fn recently_yanked(&self, within: Duration) -> Vec<&Version> {
+        self.recently_yanked_at(within, now())
+    }
+    fn recently_yanked_at(&self, within: Duration, now: DateTime<Utc>) -> Vec<&Version> {
+        let since = now - within;
+        let mut yanked: Vec<&Version> = self
+            .versions
+            .iter()
+            .filter(|v| v.yanked)
+            .filter(|v| v.created_at.map_or(false, |created_at| created_at >= since))
+            .collect();
+        yanked.sort_by_key(|v| std::cmp::Reverse(v.created_at));
+        yanked
+    }
+    /// Gets how many whole days ago the newest version was published.
+    ///
+    /// This uses `newest_version`, so yanked versions count, and versions
+    /// without a publish date are ignored. Returns `None` if no version has a
+    /// publish date.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if let Some(days) = versions.days_since_latest_release() {
+    ///     if days > 365 {
+    ///         println!("The last release was {} days ago", days);
+    ///     }
+    /// }
+    /// ```
+    pub fn days_since_latest_release(&self) -> Option<i64> {
+        self.days_since_latest_release_at(now())
+    }
+    fn days_since_latest_release_at(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.newest_version()
+            .and_then(|v| v.created_at)
+            .map(|created_at| now.signed_duration_since(created_at).num_days())
+    }
+    /// Summarizes the crate's releases in one report.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let health = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .health();
+    /// println!("{} of {} versions were yanked", health.yanked_count, health.total);
+    /// ```
+    pub fn health(&self) -> HealthReport {
+        self.health_at(now())
+    }
+    fn health_at(&self, now: DateTime<Utc>) -> HealthReport {
+        HealthReport {
+            total: self.versions.len(),
+            yanked_count: self.versions.iter().filter(|v| v.yanked).count(),
+            latest: self.max_unyanked_version_owned(),
+            days_since_last_release: self.days_since_latest_release_at(now),
+            yank_ratio: self.yank_ratio(),
+        }
+    }
+    /// Compares `self` to a `newer` list of versions for the same crate.
+    ///
+    /// This is useful when polling for new versions, to find out exactly what
+    /// changed since the last check.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let old = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// /* Some time passes */
+    /// let new = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// for version in old.diff(&new).added {
+    ///     println!("{} was released!", version);
+    /// }
+    /// ```
+    pub fn diff(&self, newer: &Versions) -> VersionsDiff {
+        let old: HashMap<&Version, bool> = self.versions.iter().map(|v| (v, v.yanked)).collect();
+        let mut diff = VersionsDiff::default();
+        for version in &newer.versions {
+            match old.get(version) {
+                None => diff.added.push(version.clone()),
+                Some(false) if version.yanked => diff.newly_yanked.push(version.clone()),
+                Some(_) => {}
+            }
+        }
+        diff
+    }
+    /// Compares `self` to an `other` list of versions for the same crate, from
+    /// a different source.
+    ///
+    /// This is useful to check if a mirror is out of sync with the registry
+    /// it mirrors. Unlike `diff`, neither list is assumed to be newer.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{FetchConfig, Versions};
+    ///
+    /// let config = FetchConfig::new("my-cool-crate/1.0.0");
+    /// let registry = Versions::fetch("my-cool-crate", &config).unwrap();
+    /// let config = config.base_url("https://mirror.example.com");
+    /// let mirror = Versions::fetch("my-cool-crate", &config).unwrap();
+    /// let report = registry.reconcile(&mirror);
+    /// for version in report.only_in_self {
+    ///     println!("The mirror is missing {}", version);
+    /// }
+    /// ```
+    pub fn reconcile(&self, other: &Versions) -> ReconcileReport {
+        let ours: HashSet<&Version> = self.versions.iter().collect();
+        let theirs: HashSet<&Version> = other.versions.iter().collect();
+        let mut report = ReconcileReport::default();
+        for version in &self.versions {
+            if !theirs.contains(version) {
+                report.only_in_self.push(version.clone());
+            }
+        }
+        for version in &other.versions {
+            match ours.get(version) {
+                None => report.only_in_other.push(version.clone()),
+                Some(ours) if ours.yanked != version.yanked => {
+                    report.yank_mismatches.push((*ours).clone())
+                }
+                Some(_) => {}
+            }
+        }
+        report
+    }
+    /// Renders the versions as a plain-text table with aligned columns.
+    ///
+    /// The columns are the version, whether it was yanked, the date it was
+    /// published, and its download count. Versions are listed in the order
+    /// they were found.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// println!("{}", versions.to_table());
+    /// ```
+    pub fn to_table(&self) -> String {
+        let header = ["VERSION", "YANKED", "PUBLISHED", "DOWNLOADS"];
+        let rows: Vec<[String; 4]> = self
+            .versions
+            .iter()
+            .map(|v| {
+                [
+                    v.version.to_string(),
+                    String::from(if v.yanked { "yes" } else { "no" }),
+                    v.created_at
+                        .map_or_else(|| String::from("-"), |d| d.date_naive().to_string()),
+                    v.downloads
+                        .map_or_else(|| String::from("-"), |d| d.to_string()),
+                ]
+            })
+            .collect();
+
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let mut table = String::new();
+        let header = header.map(String::from);
+        for row in std::iter::once(&header).chain(&rows) {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect();
+            table.push_str(line.join("  ").trim_end());
+            table.push('\n');
+        }
+        table
+    }
+    /// Creates a new `Versions` with clones of the versions that match
+    /// `predicate`.
+    ///
+    /// Information about the crate, like `total_downloads`, is kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let newest_1x = Versions::new("my-cool-crate", "my-cool-crate/1.0.0")
+    ///     .unwrap()
+    ///     .filter(|v| v.major() == 1)
+    ///     .max_stable_version()
+    ///     .cloned();
+    /// ```
+    pub fn filter<P>(&self, predicate: P) -> Versions
+    where
+        P: Fn(&Version) -> bool,
+    {
+        let versions: Vec<Version> = self
+            .versions
+            .iter()
+            .filter(|v| predicate(v))
+            .cloned()
+            .collect();
+        Versions {
+            crate_meta: self.crate_meta.clone(),
+            source_url: self.source_url.clone(),
+            ..Versions::from(versions)
+        }
+    }
+    /// Gets the URL that the versions were fetched from.
+    ///
+    /// This is `None` if the versions weren't fetched by this crate, like
+    /// when they were parsed with `from_json`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// assert_eq!(
+    ///     versions.source_url(),
+    ///     Some("https://crates.io/api/v1/crates/my-cool-crate"),
+    /// );
+    /// ```
+    pub fn source_url(&self) -> Option<&str> {
+        self.source_url.as_deref()
+    }
+    /// Gets the URL that the versions were fetched from.
+    ///
+    /// This is the same as `source_url()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// assert_eq!(
+    ///     versions.url(),
+    ///     Some("https://crates.io/api/v1/crates/my-cool-crate"),
+    /// );
+    /// ```
+    pub fn url(&self) -> Option<&str> {
+        self.source_url()
+    }
+    /// Guesses a URL where the crate's changelog can be found, from the links
+    /// that the registry reported.
+    ///
+    /// If the repository is on GitHub or GitLab, this links to the
+    /// `CHANGELOG.md` file in it. Otherwise, this is the first of the home
+    /// page, the repository, or the documentation that is set. This is a
+    /// best guess, so the link may not exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if let Some(version) = versions.max_version() {
+    ///     print!("Version {} is available", version);
+    ///     match versions.changelog_url() {
+    ///         Some(url) => println!(", see what's new at {}", url),
+    ///         None => println!(),
+    ///     }
+    /// }
+    /// ```
+    pub fn changelog_url(&self) -> Option<String> {
+        let meta = self.crate_meta.as_ref()?;
+        let repository = meta.repository.as_deref();
+        repository
+            .and_then(changelog_in_repository)
+            .or_else(|| meta.homepage.clone())
+            .or_else(|| repository.map(String::from))
+            .or_else(|| meta.documentation.clone())
+    }
+    /// Compares every version to `rhs`, in the same order as `versions`.
+    ///
+    /// `rhs` is parsed only once, unlike comparing each version with `<` or
+    /// `partial_cmp`, which parses the string again for every comparison.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    /// use std::cmp::Ordering;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// let newer = versions
+    ///     .compare_all_against("1.0.0")
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .filter(|ordering| *ordering == Ordering::Greater)
+    ///     .count();
+    /// println!("{} versions are newer than 1.0.0", newer);
+    /// ```
+    pub fn compare_all_against(&self, rhs: &str) -> Result<Vec<Ordering>> {
+        let rhs = SemVer::parse(rhs).map_err(|e| anyhow!("Couldn't parse version: {}", e))?;
+        Ok(self.versions.iter().map(|v| v.version.cmp(&rhs)).collect())
+    }
+    /// Gets the full list of versions that were found.
+    pub fn versions(&self) -> &Vec<Version> {
+        &self.versions
+    }
+    /// Iterates over the versions that haven't been yanked, in the same
+    /// order as `versions`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// for version in versions.iter_unyanked() {
+    ///     println!("{}", version);
+    /// }
+    /// ```
+    pub fn iter_unyanked(&self) -> impl Iterator<Item = &Version> + '_ {
+        self.versions.iter().filter(|v| !v.yanked)
+    }
+    /// Iterates over the stable versions, in the same order as `versions`.
+    ///
+    /// A stable version is one that hasn't been yanked and isn't a
+    /// pre-release.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// for version in versions.iter_stable() {
+    ///     println!("{}", version);
+    /// }
+    /// ```
+    pub fn iter_stable(&self) -> impl Iterator<Item = &Version> + '_ {
+        self.versions.iter().filter(|v| v.is_stable())
+    }
+    /// Gets a mutable list of versions that were found.
+    ///
+    /// Because the list may be changed, the remembered result of
+    /// `max_unyanked_version` is forgotten. Information about the crate, like
+    /// `total_downloads`, is kept.
+    pub fn versions_mut(&mut self) -> &mut Vec<Version> {
+        self.max_unyanked = OnceCell::new();
+        &mut self.versions
+    }
+    /// Takes ownership of `self` and returns owned versions list.
+    pub fn versions_owned(self) -> Vec<Version> {
+        self.versions
+    }
+}
+
+impl Version {
+    /// Gets the next version after a `kind` bump, like `1.3.0` for a
+    /// `BumpKind::Minor` bump from `1.2.3`.
+    ///
+    /// The lower versions are reset to `0`, and any pre-release or build
+    /// metadata is removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{BumpKind, Versions};
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if let Some(max) = versions.max_version() {
+    ///     let next = max.bumped(BumpKind::Minor);
+    ///     assert!(!versions.is_taken(&next));
+    /// }
+    /// ```
+    pub fn bumped(&self, kind: BumpKind) -> SemVer {
+        let SemVer {
+            major,
+            minor,
+            patch,
+            ..
+        } = self.version;
+        match kind {
+            BumpKind::Major => SemVer::new(major + 1, 0, 0),
+            BumpKind::Minor => SemVer::new(major, minor + 1, 0),
+            BumpKind::Patch => SemVer::new(major, minor, patch + 1),
+        }
+    }
+    /// Checks if this version is in `channel`.
+    fn in_channel(&self, channel: &Channel) -> bool {
+        match channel {
+            Channel::Stable => self.is_stable(),
+            Channel::Prerelease => !self.yanked,
+            Channel::Any => true,
+            Channel::Named(name) => {
+                !self.yanked && self.version.pre.as_str().split('.').next() == Some(name)
+            }
+        }
+    }
+    /// Gets the version as a string, like `"1.0.0-rc.1+build.5"`, without the
+    /// `(yanked)` that `Display` adds.
+    ///
+    /// Versions that were parsed strictly, which is the default, are exactly
+    /// the same as the registry published them, including any pre-release
+    /// and build metadata. Versions that were parsed with
+    /// `FetchConfig::lenient_versions` are normalized, so `v01.2.3` becomes
+    /// `1.2.3`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use check_latest::Versions;
+    ///
+    /// let body = r#"{"versions": [{"num": "1.0.0-rc.1+build.5", "yanked": true, "created_at": null}]}"#;
+    /// let versions = Versions::from_json(body).unwrap();
+    /// assert_eq!(
+    ///     versions.versions()[0].to_published_string(),
+    ///     "1.0.0-rc.1+build.5",
+    /// );
+    /// ```
+    pub fn to_published_string(&self) -> String {
+        self.version.to_string()
+    }
+    /// Gets the URL to download this version of `crate_name` from
+    /// [Crates.io], as a `.crate` archive.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use check_latest::Versions;
+    ///
+    /// let body = r#"{"versions": [{"num": "1.2.3", "yanked": false, "created_at": null}]}"#;
+    /// let versions = Versions::from_json(body).unwrap();
+    /// assert_eq!(
+    ///     versions.max_version().unwrap().download_url("my-cool-crate"),
+    ///     "https://crates.io/api/v1/crates/my-cool-crate/1.2.3/download",
+    /// );
+    /// ```
+    ///
+    /// [Crates.io]: https://crates.io/
+    pub fn download_url(&self, crate_name: &str) -> String {
+        self.download_url_with(crate_name, &FetchConfig::default())
+    }
+    /// Like `download_url`, but for the registry that `config` fetches from.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use check_latest::{FetchConfig, Versions};
+    ///
+    /// let body = r#"{"versions": [{"num": "1.2.3", "yanked": false, "created_at": null}]}"#;
+    /// let versions = Versions::from_json(body).unwrap();
+    /// let config = FetchConfig::new("my-cool-crate/1.0.0").base_url("https://mirror.example.com");
+    /// assert_eq!(
+    ///     versions.max_version().unwrap().download_url_with("my-cool-crate", &config),
+    ///     "https://mirror.example.com/api/v1/crates/my-cool-crate/1.2.3/download",
+    /// );
+    /// ```
+    pub fn download_url_with(&self, crate_name: &str, config: &FetchConfig) -> String {
+        format!(
+            "{}/{}/download",
+            build_url(&config.resolved_api_root(), crate_name),
+            self.version,
+        )
+    }
+    /// Gets the SemVer MAJOR version
+    pub fn major(&self) -> u64 {
+        self.version.major
+    }
+    /// Gets the SemVer MINOR version
+    pub fn minor(&self) -> u64 {
+        self.version.minor
+    }
+    /// Gets the SemVer PATCH version
+    pub fn patch(&self) -> u64 {
+        self.version.patch
+    }
+    /// Gets how significant the update from `current` to this version is.
+    ///
+    /// Like Cargo, this treats the left-most non-zero component as the MAJOR
+    /// version. So an update from `0.1.0` to `0.2.0`, or from `0.0.1` to
+    /// `0.0.2`, is `BumpKind::Major`.
+    ///
+    /// Returns `None` if this version isn't greater than `current`.
+    pub fn bump_kind_from(&self, current: &SemVer) -> Option<BumpKind> {
+        if self.version <= *current {
+            None
+        } else if !self.is_compatible_with(current) {
+            Some(BumpKind::Major)
+        } else if self.minor() != current.minor {
+            Some(BumpKind::Minor)
+        } else {
+            Some(BumpKind::Patch)
+        }
+    }
+    /// If this version is SemVer compatible with `current`, following the same
+    /// rules as Cargo.
+    ///
+    /// - `1.2.3` is compatible with `1.y.z`
+    /// - `0.2.3` is compatible with `0.2.z`
+    /// - `0.0.3` is only compatible with `0.0.3`
+    pub fn is_compatible_with(&self, current: &SemVer) -> bool {
+        match (current.major, current.minor) {
+            (0, 0) => self.major() == 0 && self.minor() == 0 && self.patch() == current.patch,
+            (0, minor) => self.major() == 0 && self.minor() == minor,
+            (major, _) => self.major() == major,
+        }
+    }
+    /// Describes this version and how long ago it was published, like
+    /// `"1.2.3 — published 5 days ago"`.
+    ///
+    /// If the registry didn't report when this version was published, only
+    /// the version is included.
+    pub fn describe(&self) -> String {
+        self.describe_at(now())
+    }
+    fn describe_at(&self, now: DateTime<Utc>) -> String {
+        match self.created_at {
+            Some(created_at) => format!(
+                "{} \u{2014} published {}",
+                self,
+                humanize_age(now.signed_duration_since(created_at))
+            ),
+            None => self.to_string(),
+        }
+    }
+    /// Suggests a version requirement that can be copied into `Cargo.toml`.
+    ///
+    /// Build metadata is left out, since it is ignored in requirements. A
+    /// pre-release always uses the full version, even with
+    /// `ReqStyle::MajorMinor`, because otherwise it wouldn't be matched.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::{ReqStyle, Versions};
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// if let Some(max) = versions.max_stable_version() {
+    ///     println!(
+    ///         "my-cool-crate = \"{}\"",
+    ///         max.suggest_requirement(ReqStyle::MajorMinor),
+    ///     );
+    /// }
+    /// ```
+    pub fn suggest_requirement(&self, style: ReqStyle) -> String {
+        let version = &self.version;
+        let full = if version.pre.is_empty() {
+            format!("{}.{}.{}", version.major, version.minor, version.patch)
+        } else {
+            format!(
+                "{}.{}.{}-{}",
+                version.major, version.minor, version.patch, version.pre
+            )
+        };
+        match style {
+            ReqStyle::Caret => full,
+            ReqStyle::Exact => format!("={}", full),
+            ReqStyle::MajorMinor if version.pre.is_empty() => {
+                format!("{}.{}", version.major, version.minor)
+            }
+            ReqStyle::MajorMinor => full,
+        }
+    }
+    /// Gets the URL of this version's page on [Crates.io].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::*;
+    ///
+    /// if let Ok(Some(version)) = check_max!() {
+    ///     println!(
+    ///         "Version {} is now available! See {}",
+    ///         version,
+    ///         version.crates_io_url(crate_name!()),
+    ///     );
+    /// }
+    /// ```
+    ///
+    /// [Crates.io]: https://crates.io/
+    pub fn crates_io_url(&self, crate_name: &str) -> String {
+        build_page_url(crate_name, &self.version)
+    }
+    /// Gets why this version was yanked, if the registry reported it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// for version in versions.versions().iter().filter(|v| v.yanked) {
+    ///     match version.yank_reason() {
+    ///         Some(reason) => println!("{} was yanked: {}", version, reason),
+    ///         None => println!("{} was yanked", version),
+    ///     }
+    /// }
+    /// ```
+    pub fn yank_reason(&self) -> Option<&str> {
+        self.yank_message.as_deref()
+    }
+    /// Gets a value that displays this version with both `(pre-release)` and
+    /// `(yanked)` annotations, where the `Display` implementation of
+    /// `Version` only annotates `(yanked)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use check_latest::Versions;
+    ///
+    /// let versions = Versions::new("my-cool-crate", "my-cool-crate/1.0.0").unwrap();
+    /// for version in versions.versions() {
+    ///     // Like "1.0.0-rc.1 (pre-release) (yanked)"
+    ///     println!("{}", version.display_verbose());
+    /// }
+    /// ```
+    pub fn display_verbose(&self) -> VerboseDisplay<'_> {
+        VerboseDisplay(self)
+    }
+    /// If this version is a pre-release, like `1.0.0-rc.1`
+    pub fn is_prerelease(&self) -> bool {
+        !self.version.pre.is_empty()
+    }
+    /// If this version hasn't been yanked and isn't a pre-release
+    pub fn is_stable(&self) -> bool {
+        !self.yanked && !self.is_prerelease()
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, rhs: &Version) -> bool {
+        self.version.eq(&rhs.version)
+    }
+}
+
+impl Eq for Version {}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.version.hash(state);
+    }
+}
+
+impl PartialEq<SemVer> for Version {
+    fn eq(&self, rhs: &SemVer) -> bool {
+        self.version.eq(rhs)
+    }
+}
+
+impl PartialEq<str> for Version {
+    fn eq(&self, rhs: &str) -> bool {
+        match SemVer::parse(rhs) {
+            Ok(version) => self.eq(&version),
+            Err(_) => false,
+        }
+    }
+}
+
+impl PartialEq<&str> for Version {
+    fn eq(&self, rhs: &&str) -> bool {
+        self.eq(rhs.to_owned())
+    }
+}
+
+impl PartialOrd<SemVer> for Version {
+    fn partial_cmp(&self, rhs: &SemVer) -> Option<Ordering> {
+        self.version.partial_cmp(rhs)
+    }
+}
+
+impl PartialOrd<str> for Version {
+    fn partial_cmp(&self, rhs: &str) -> Option<Ordering> {
+        match SemVer::parse(rhs) {
+            Ok(version) => self.partial_cmp(&version),
+            Err(_) => None,
+        }
+    }
+}
+
+impl PartialOrd<&str> for Version {
+    fn partial_cmp(&self, rhs: &&str) -> Option<Ordering> {
+        self.partial_cmp(rhs.to_owned())
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.version)?;
+        if self.yanked {
+            write!(f, " (yanked)")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Displays a `Version` with all of its annotations.
+///
+/// Created with `Version::display_verbose`.
+#[derive(Clone, Copy, Debug)]
+pub struct VerboseDisplay<'a>(&'a Version);
+
+impl Display for VerboseDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.version)?;
+        if self.0.is_prerelease() {
+            write!(f, " (pre-release)")?;
+        }
+        if self.0.yanked {
+            write!(f, " (yanked)")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Vec<Version>> for Versions {
+    fn from(versions: Vec<Version>) -> Versions {
+        Versions {
+            versions,
+            crate_meta: None,
+            source_url: None,
+            max_unyanked: OnceCell::new(),
+        }
+    }
+}
+
+impl AsRef<[Version]> for Versions {
+    fn as_ref(&self) -> &[Version] {
+        &self.versions
+    }
+}
+
+impl From<UpdateCheck> for Option<Version> {
+    fn from(check: UpdateCheck) -> Option<Version> {
+        match check {
+            UpdateCheck::UpdateAvailable(version) => Some(version),
+            UpdateCheck::UpToDate | UpdateCheck::Ahead => None,
+        }
+    }
+}
+
+impl From<&Version> for InstallTarget {
+    fn from(v: &Version) -> InstallTarget {
+        InstallTarget {
+            version: v.version.to_string(),
+            yanked: v.yanked,
+        }
+    }
+}
+
+impl TryFrom<&Versions> for LatestInfo {
+    type Error = anyhow::Error;
+
+    fn try_from(versions: &Versions) -> Result<LatestInfo> {
+        let max_version = versions
+            .max_unyanked_version_owned()
+            .context("Couldn't find a version that hasn't been yanked")?;
+        Ok(LatestInfo {
+            max_version,
+            max_stable_version: versions.max_stable_version().cloned(),
+        })
+    }
+}
+
+impl From<Version> for SemVer {
+    fn from(v: Version) -> SemVer {
+        v.version
+    }
+}
+
+/// The error when a response is larger than the limit set with
+/// [`FetchConfig::max_response_size`].
+///
+/// The error is wrapped in an `anyhow::Error`, and can be checked for with
+/// `downcast_ref`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use check_latest::{FetchConfig, ResponseTooLarge, Versions};
+///
+/// let config = FetchConfig::new("my-cool-crate/1.0.0").max_response_size(1024);
+/// if let Err(e) = Versions::fetch("my-cool-crate", &config) {
+///     if let Some(too_large) = e.downcast_ref::<ResponseTooLarge>() {
+///         println!("The response was over {} bytes", too_large.limit);
+///     }
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResponseTooLarge {
+    /// The limit that the response was larger than, in bytes
+    pub limit: usize,
+}
+
+impl Display for ResponseTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The response was larger than {} bytes", self.limit)
+    }
+}
+
+impl std::error::Error for ResponseTooLarge {}
+
+/// Errors with [`ResponseTooLarge`] if `size` is over `limit`.
+#[cfg(any(feature = "async", feature = "blocking"))]
+fn check_response_size(size: usize, limit: usize) -> Result<()> {
+    if size > limit {
+        Err(ResponseTooLarge { limit }.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// The maximum number of redirects that will be followed for a single request.
+#[cfg(any(feature = "async", feature = "blocking"))]
+const MAX_REDIRECTS: usize = 5;
+
+/// Follows at most [`MAX_REDIRECTS`] redirects, and only if they stay on the
+/// same origin as the original request. This keeps headers from being sent to
+/// a host other than the one that was requested.
+#[cfg(any(feature = "async", feature = "blocking"))]
+fn redirect_policy() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        let same_origin = attempt
+            .previous()
+            .first()
+            .map_or(false, |url| url.origin() == attempt.url().origin());
+        if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if !same_origin {
+            attempt.error("refusing to follow a redirect to another origin")
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// Builds the URL to request a crate's versions from.
+///
+/// This is useful if you want to make the request with your own HTTP client,
+/// and then parse the response with `Versions::from_json`.
+///
+/// # Example
+///
+/// ```rust
+/// use check_latest::build_url;
+///
+/// let url = build_url("https://crates.io/api/v1", "my-cool-crate");
+/// assert_eq!(url, "https://crates.io/api/v1/crates/my-cool-crate");
+/// ```
+pub fn build_url(api_root: &str, crate_name: &str) -> String {
+    format!(
+        "{api_root}/crates/{crate_name}",
+        api_root = api_root,
+        crate_name = crate_name,
+    )
+}
+
+/// Builds a user agent for making requests, from a version that's only known
+/// at runtime.
+///
+/// This is formatted the same way as `user_agent!`, `"<name>/<version>"`.
+///
+/// # Example
+///
+/// ```rust
+/// use check_latest::user_agent_for;
+///
+/// let version = semver::Version::new(1, 2, 3);
+/// assert_eq!(user_agent_for("my-cool-crate", &version), "my-cool-crate/1.2.3");
+/// ```
+pub fn user_agent_for(name: &str, version: &SemVer) -> String {
+    format!("{}/{}", name, version)
+}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+mod check;
+mod config;
+mod denylist;
+mod index;
+
+pub use config::{set_default_user_agent, FetchConfig};
+pub use denylist::load_denylist;
+
+/// Gets the current time without requiring chrono's `clock` feature.
+fn now() -> DateTime<Utc> {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Utc.timestamp_opt(since_epoch.as_secs() as i64, since_epoch.subsec_nanos())
+        .single()
+        .unwrap_or_default()
+}
+
+/// Formats an age in days, weeks, months, or years.
+fn humanize_age(age: Duration) -> String {
+    let days = age.num_days();
+    let (count, unit) = match days {
+        i64::MIN..=0 => return String::from("today"),
+        1..=13 => (days, "day"),
+        14..=59 => (days / 7, "week"),
+        60..=364 => (days / 30, "month"),
+        _ => (days / 365, "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{} {}{} ago", count, unit, plural)
+}
+
+fn build_page_url(crate_name: &str, version: &SemVer) -> String {
+    format!(
+        "https://crates.io/crates/{crate_name}/{version}",
+        crate_name = crate_name,
+        version = version,
+    )
+}
+
+/// Check for version updates with asynchronous requests.
+#[cfg(feature = "async")]
+pub mod r#async;
+
+/// Check for version updates with blocking requests.
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+/// Check for version updates from C, or other languages with a C FFI.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// Test your own update checks against a local server.
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+/// Not part of the public API. Used by macros that generate code in *your*
+/// crate.
+#[doc(hidden)]
+pub mod __private {
+    pub use anyhow::Result;
+
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub use crate::check::CheckOptions;
+
+    use crate::{Version, Versions};
+    use anyhow::Context;
+    use semver::Version as SemVer;
+
+    /// Parses the current version, so that it only needs to be parsed once.
+    fn parse_current(current: &str) -> Result<SemVer> {
+        crate::parse_version_lenient(current).context("Couldn't parse current version")
+    }
+
+    /// Gets the max version that hasn't been yanked, matches `filter`, and
+    /// is greater than `current`.
+    fn update_where<F>(
+        versions: &Versions,
+        current: &SemVer,
+        include_prerelease: bool,
+        filter: F,
+    ) -> Option<Version>
+    where
+        F: Fn(&Version) -> bool,
+    {
+        versions
+            .versions()
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter(|v| include_prerelease || !v.is_prerelease())
+            .filter(|v| filter(v))
+            .filter(|v| v.version > *current)
+            .max_by(|v1, v2| crate::cmp_for_max(v1, v2))
+            .cloned()
+    }
+
+    /// Gets the update with any version. Used by `check_max!`.
+    pub fn max_update(
+        versions: &Versions,
+        current: &str,
+        include_prerelease: bool,
+    ) -> Result<Option<Version>> {
+        let current = parse_current(current)?;
+        let max = if include_prerelease {
+            versions.max_unyanked_version()
+        } else {
+            versions.max_stable_version()
+        };
+        Ok(max.filter(|max| max.version > current).cloned())
+    }
+
+    /// Gets the update with the same MAJOR version. Used by `check_minor!`.
+    pub fn minor_update(
+        versions: &Versions,
+        current: &str,
+        include_prerelease: bool,
+    ) -> Result<Option<Version>> {
+        let current = parse_current(current)?;
+        Ok(update_where(versions, &current, include_prerelease, |v| {
+            v.major() == current.major
+        }))
+    }
+
+    /// Gets the update with the same MAJOR and MINOR versions. Used by
+    /// `check_patch!`.
+    pub fn patch_update(
+        versions: &Versions,
+        current: &str,
+        include_prerelease: bool,
+    ) -> Result<Option<Version>> {
+        let current = parse_current(current)?;
+        Ok(update_where(versions, &current, include_prerelease, |v| {
+            v.major() == current.major && v.minor() == current.minor
+        }))
+    }
+}
+
+/// Gets the name of the crate as defined in *your* `Cargo.toml`.
+#[macro_export]
+macro_rules! crate_name {
+    () => {
+        env!("CARGO_PKG_NAME")
+    };
+}
+
+/// Gets the version of the crate as defined in *your* `Cargo.toml`.
+///
+/// Will be `&str`
+#[macro_export]
+macro_rules! crate_version {
+    () => {
+        env!("CARGO_PKG_VERSION")
+    };
+}
+
+/// Gets the major version of the crate as defined in *your* `Cargo.toml`.
+///
+/// Will be `&str`
+#[macro_export]
+macro_rules! crate_major_version {
+    () => {
+        env!("CARGO_PKG_VERSION_MAJOR")
+    };
+}
+
+/// Gets the minor version of the crate as defined in *your* `Cargo.toml`.
+///
+/// Will be `&str`
+#[macro_export]
+macro_rules! crate_minor_version {
+    () => {
+        env!("CARGO_PKG_VERSION_MINOR")
+    };
+}
+
+/// Gets the patch version of the crate as defined in *your* `Cargo.toml`.
+///
+/// Will be `&str`
+#[macro_export]
+macro_rules! crate_patch {
+    () => {
+        env!("CARGO_PKG_VERSION_PATCH")
+    };
+}
+
+/// Defines an appropriate user agent for making requests.
+///
+/// `"<your-crate-name>/<version>"`
+#[macro_export]
+macro_rules! user_agent {
+    () => {
+        concat!($crate::crate_name!(), "/", $crate::crate_version!())
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lazy_static::lazy_static;
+
+    lazy_static! {
+        static ref DONT_CARE_DATETIME: DateTime<Utc> = Utc.timestamp_opt(0, 0).unwrap();
+    }
+
+    fn version(num: &str, yanked: bool) -> Version {
+        Version {
+            version: SemVer::parse(num).unwrap(),
+            yanked,
+            created_at: Some(*DONT_CARE_DATETIME),
+            downloads: None,
+            rust_version: None,
+            yank_message: None,
+        }
+    }
+
+    /// Builds a list of versions from `(num, yanked)` pairs. Also used by the
+    /// tests of other modules.
+    pub(crate) fn versions(list: &[(&str, bool)]) -> Versions {
+        Versions::from(
+            list.iter()
+                .map(|&(num, yanked)| version(num, yanked))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn is_greater_semver() {
+        let version = version("1.2.3", false);
+        let semver = SemVer::parse("1.2.0").unwrap();
+        assert!(version > semver);
+    }
+
+    #[test]
+    fn is_lesser_semver() {
+        let version = version("1.2.3", false);
+        let semver = SemVer::parse("1.3.0").unwrap();
+        assert!(version < semver);
+    }
+
+    #[test]
+    fn is_greater_str() {
+        let version = version("1.2.3", false);
+        assert!(version > "1.2.0");
+    }
+
+    #[test]
+    fn is_lesser_str() {
+        let version = version("1.2.3", false);
+        assert!(version < "1.3.0");
+    }
+
+    #[test]
+    fn newest_stable_skips_prereleases_and_yanked() {
+        let mut versions = versions(&[("1.0.0", false), ("1.1.0-rc.1", false), ("1.0.1", true)]);
+        for (days, version) in versions.versions.iter_mut().enumerate() {
+            version.created_at = Some(*DONT_CARE_DATETIME + Duration::days(days as i64));
+        }
+        assert_eq!(versions.newest_stable_version().unwrap(), &"1.0.0");
+        assert_eq!(versions.max_stable_version().unwrap(), &"1.0.0");
+    }
+
+    #[test]
+    fn hash_ignores_yanked() {
+        use std::collections::HashSet;
+
+        let set: HashSet<Version> = vec![version("1.0.0", false), version("1.0.0", true)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn max_version_in_range_bounds() {
+        let versions = versions(&[
+            ("1.1.0", false),
+            ("1.2.0", false),
+            ("1.4.9", true),
+            ("1.5.0", false),
+        ]);
+        let floor = SemVer::parse("1.2.0").unwrap();
+        let ceiling = SemVer::parse("1.5.0").unwrap();
+
+        let inclusive = versions.max_version_in_range(&floor, &ceiling, true);
+        assert_eq!(inclusive.unwrap(), &"1.5.0");
+        let exclusive = versions.max_version_in_range(&floor, &ceiling, false);
+        assert_eq!(exclusive.unwrap(), &"1.4.9");
+
+        let ceiling = SemVer::parse("1.2.0").unwrap();
+        assert_eq!(
+            versions
+                .max_version_in_range(&floor, &ceiling, true)
+                .unwrap(),
+            &"1.2.0"
+        );
+        assert!(versions
+            .max_version_in_range(&floor, &ceiling, false)
+            .is_none());
+    }
+
+    #[test]
+    fn diff_finds_added_and_newly_yanked() {
+        let old = versions(&[("1.0.0", false), ("1.1.0", false), ("1.2.0", true)]);
+        let new = versions(&[
+            ("1.0.0", false),
+            ("1.1.0", true),
+            ("1.2.0", true),
+            ("1.3.0", false),
+        ]);
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec![version("1.3.0", false)]);
+        assert_eq!(diff.newly_yanked, vec![version("1.1.0", true)]);
+
+        let diff = new.diff(&new);
+        assert!(diff.added.is_empty());
+        assert!(diff.newly_yanked.is_empty());
+    }
+
+    #[test]
+    fn bump_kind() {
+        let current = SemVer::parse("1.2.3").unwrap();
+        assert_eq!(version("1.2.3", false).bump_kind_from(&current), None);
+        assert_eq!(version("1.2.2", false).bump_kind_from(&current), None);
+        assert_eq!(
+            version("1.2.4-rc.1", false).bump_kind_from(&current),
+            Some(BumpKind::Patch)
+        );
+        assert_eq!(
+            version("1.3.0", false).bump_kind_from(&current),
+            Some(BumpKind::Minor)
+        );
+        assert_eq!(
+            version("2.0.0", false).bump_kind_from(&current),
+            Some(BumpKind::Major)
+        );
+    }
+
+    #[test]
+    fn bump_kind_zero_major() {
+        let current = SemVer::parse("0.1.0").unwrap();
+        assert_eq!(
+            version("0.1.1", false).bump_kind_from(&current),
+            Some(BumpKind::Patch)
+        );
+        assert_eq!(
+            version("0.2.0", false).bump_kind_from(&current),
+            Some(BumpKind::Major)
+        );
+        assert!(version("0.1.9", false).is_compatible_with(&current));
+        assert!(!version("0.2.0", false).is_compatible_with(&current));
+
+        let current = SemVer::parse("0.0.1").unwrap();
+        assert_eq!(
+            version("0.0.2", false).bump_kind_from(&current),
+            Some(BumpKind::Major)
+        );
+        assert!(!version("0.0.2", false).is_compatible_with(&current));
+    }
+
+    #[test]
+    fn max_update_ignores_smaller_bumps() {
+        let versions = versions(&[("1.2.3", false), ("1.2.9", false), ("1.3.0", true)]);
+        let current = SemVer::parse("1.2.3").unwrap();
+        assert_eq!(
+            versions.max_update(&current, BumpKind::Patch).unwrap(),
+            &"1.2.9"
+        );
+        assert!(versions.max_update(&current, BumpKind::Minor).is_none());
+    }
+
+    #[test]
+    fn explain_no_update() {
+        let current = SemVer::parse("1.0.0").unwrap();
+        let reason = |list: &[(&str, bool)]| versions(list).explain_no_update(&current);
+        assert_eq!(reason(&[("1.0.0", false)]), Some(NoUpdateReason::UpToDate));
+        assert_eq!(
+            reason(&[("1.0.0", false), ("1.1.0", true)]),
+            Some(NoUpdateReason::OnlyYanked)
+        );
+        assert_eq!(
+            reason(&[("1.1.0", true), ("1.1.0-rc.1", false)]),
+            Some(NoUpdateReason::OnlyPrerelease)
+        );
+        assert_eq!(reason(&[("1.1.0-rc.1", false), ("1.0.1", false)]), None);
+    }
+
+    #[test]
+    fn describe() {
+        let version = version("1.2.3", false);
+        let at = |days| version.describe_at(*DONT_CARE_DATETIME + Duration::days(days));
+        assert_eq!(at(0), "1.2.3 \u{2014} published today");
+        assert_eq!(at(1), "1.2.3 \u{2014} published 1 day ago");
+        assert_eq!(at(5), "1.2.3 \u{2014} published 5 days ago");
+        assert_eq!(at(21), "1.2.3 \u{2014} published 3 weeks ago");
+        assert_eq!(at(90), "1.2.3 \u{2014} published 3 months ago");
+        assert_eq!(at(800), "1.2.3 \u{2014} published 2 years ago");
+    }
+
+    #[test]
+    fn ignores_reported_max() {
+        let json = r#"{
+            "versions": [
+                {"num": "1.0.0", "yanked": false, "created_at": null},
+                {"num": "1.1.0", "yanked": false, "created_at": null},
+                {"num": "2.0.0", "yanked": true, "created_at": null}
+            ],
+            "crate": {"max_version": "1.0.0", "max_stable_version": "1.0.0"}
+        }"#;
+        let list = Versions::from_json(json).unwrap();
+        assert_eq!(list.max_unyanked_version().unwrap(), &"1.1.0");
+        assert_eq!(list.max_stable_version().unwrap(), &"1.1.0");
+    }
+
+    #[test]
+    fn yank_ratio() {
+        assert_eq!(versions(&[]).yank_ratio(), 0.0);
+        let versions = versions(&[
+            ("1.0.0", true),
+            ("1.1.0", false),
+            ("1.2.0", false),
+            ("1.3.0", false),
+        ]);
+        assert_eq!(versions.yank_ratio(), 0.25);
+    }
+
+    #[test]
+    fn most_recent_major_bump() {
+        let mut versions = versions(&[
+            ("1.0.0", false),
+            ("2.0.0", false),
+            ("1.5.0", false),
+            ("2.1.0", false),
+        ]);
+        for (days, version) in versions.versions.iter_mut().enumerate() {
+            version.created_at = Some(*DONT_CARE_DATETIME + Duration::days(days as i64));
+        }
+        assert_eq!(versions.most_recent_major_bump().unwrap(), &"2.0.0");
+    }
+
+    #[test]
+    fn total_downloads() {
+        let json = r#"{"crate": {"downloads": 1234}, "versions": []}"#;
+        assert_eq!(
+            Versions::from_json(json).unwrap().total_downloads(),
+            Some(1234)
+        );
+        let json = r#"{"versions": []}"#;
+        assert_eq!(Versions::from_json(json).unwrap().total_downloads(), None);
+    }
+
+    #[test]
+    fn filter() {
+        let versions = versions(&[("1.0.0", false), ("1.1.0", true), ("2.0.0", false)]);
+        let filtered = versions.filter(|v| v.major() == 1);
+        assert_eq!(filtered.versions().len(), 2);
+        assert_eq!(filtered.max_stable_version().unwrap(), &"1.0.0");
+    }
+
+    #[test]
+    fn sequence_gaps() {
+        let versions = versions(&[
+            ("1.0.0", false),
+            ("1.0.3", true),
+            ("1.0.1-rc.1", false),
+            ("1.1.1", false),
+            ("2.0.0", false),
+        ]);
+        let gaps: Vec<String> = versions
+            .sequence_gaps()
+            .iter()
+            .map(SemVer::to_string)
+            .collect();
+        assert_eq!(gaps, vec!["1.0.1", "1.0.2"]);
+    }
+
+    #[test]
+    fn rust_version_parsing() {
+        assert_eq!(parse_rust_version("1.60"), Some(SemVer::new(1, 60, 0)));
+        assert_eq!(parse_rust_version("1.60.1"), Some(SemVer::new(1, 60, 1)));
+        assert_eq!(parse_rust_version("1"), Some(SemVer::new(1, 0, 0)));
+        assert_eq!(parse_rust_version("1.x"), None);
+        assert_eq!(parse_rust_version("1.2.3.4"), None);
+        assert_eq!(parse_rust_version(""), None);
+    }
+
+    #[test]
+    fn max_update_within_msrv() {
+        let json = r#"{"versions": [
+            {"num": "1.0.0", "yanked": false, "created_at": null, "rust_version": "1.56"},
+            {"num": "1.1.0", "yanked": false, "created_at": null},
+            {"num": "1.2.0", "yanked": false, "created_at": null, "rust_version": "1.70"}
+        ]}"#;
+        let versions = Versions::from_json(json).unwrap();
+        let current = SemVer::parse("1.0.0").unwrap();
+        let toolchain = SemVer::parse("1.65.0").unwrap();
+        let update = versions.max_update_within_msrv(&current, &toolchain);
+        assert_eq!(update.unwrap(), &"1.1.0");
+    }
+
+    #[test]
+    fn oldest_and_earliest_unyanked() {
+        let mut versions = versions(&[("0.9.0", true), ("1.1.0", false), ("1.0.0", false)]);
+        for (days, version) in versions.versions.iter_mut().enumerate() {
+            version.created_at = Some(*DONT_CARE_DATETIME + Duration::days(days as i64));
+        }
+        assert_eq!(versions.oldest_unyanked_version().unwrap(), &"1.0.0");
+        assert_eq!(versions.earliest_unyanked_version().unwrap(), &"1.1.0");
+    }
+
+    #[test]
+    fn from_vec_and_as_ref() {
+        fn count_yanked(versions: &[Version]) -> usize {
+            versions.iter().filter(|v| v.yanked).count()
+        }
+
+        let versions = Versions::from(vec![version("1.0.0", true), version("1.1.0", false)]);
+        assert_eq!(versions.versions().len(), 2);
+        assert_eq!(count_yanked(versions.as_ref()), 1);
+    }
+
+    #[test]
+    fn behind_versions() {
+        let versions = versions(&[("1.0.0", false), ("1.2.0", false), ("1.3.0", true)]);
+        let currents = [
+            SemVer::parse("1.0.0").unwrap(),
+            SemVer::parse("1.2.0").unwrap(),
+            SemVer::parse("0.9.0").unwrap(),
+        ];
+        let behind: Vec<(String, &Version)> = versions
+            .behind_versions(&currents)
+            .into_iter()
+            .map(|(current, update)| (current.to_string(), update))
+            .collect();
+        assert_eq!(behind.len(), 2);
+        assert_eq!(behind[0].0, "1.0.0");
+        assert_eq!(behind[0].1, &"1.2.0");
+        assert_eq!(behind[1].0, "0.9.0");
+    }
+
+    #[test]
+    fn install_target() {
+        let versions = versions(&[("1.0.0", false), ("1.1.0-beta.1", false), ("1.2.0", true)]);
+        let target = |channel| versions.install_target(channel).unwrap();
+        assert_eq!(
+            target(Channel::Stable),
+            InstallTarget {
+                version: String::from("1.0.0"),
+                yanked: false,
+            }
+        );
+        assert_eq!(target(Channel::Prerelease).version, "1.1.0-beta.1");
+        assert_eq!(
+            target(Channel::Any),
+            InstallTarget {
+                version: String::from("1.2.0"),
+                yanked: true,
+            }
+        );
+        assert!(Versions::from(Vec::new())
+            .install_target(Channel::Any)
+            .is_none());
+    }
+
+    #[test]
+    fn current_is_yanked() {
+        let versions = versions(&[("1.0.0", false), ("1.0.1", true), ("1.0.2", false)]);
+        assert!(versions.current_is_yanked(&SemVer::parse("1.0.1").unwrap()));
+        assert!(!versions.current_is_yanked(&SemVer::parse("1.0.0").unwrap()));
+        assert!(!versions.current_is_yanked(&SemVer::parse("2.0.0").unwrap()));
+
+        let list = Versions::from(vec![version("1.0.0", false), version("1.1.0", true)]);
+        let current = SemVer::parse("1.1.0").unwrap();
+        assert_eq!(list.max_version().unwrap(), &"1.1.0");
+        assert!(list.current_is_yanked(&current));
+    }
+
+    #[test]
+    fn is_current_yanked() {
+        let list = versions(&[("1.0.0", false), ("1.0.1", true)]);
+        assert!(list.is_current_yanked(&SemVer::parse("1.0.1").unwrap()));
+        assert!(!list.is_current_yanked(&SemVer::parse("1.0.0").unwrap()));
+        assert!(!list.is_current_yanked(&SemVer::parse("2.0.0").unwrap()));
+    }
+
+    #[test]
+    fn max_version_with() {
+        let versions = versions(&[("1.0.0", false), ("2.0.0", true), ("1.5.0", false)]);
+        let unyanked_first = versions
+            .max_version_with(|v1, v2| {
+                v2.yanked
+                    .cmp(&v1.yanked)
+                    .then_with(|| v1.version.cmp(&v2.version))
+            })
+            .unwrap();
+        assert_eq!(unyanked_first, &"1.5.0");
+        let lowest = versions
+            .max_version_with(|v1, v2| v2.major().cmp(&v1.major()))
+            .unwrap();
+        assert_eq!(lowest, &"1.5.0");
+        assert!(Versions::from(Vec::new())
+            .max_version_with(|_, _| Ordering::Equal)
+            .is_none());
+    }
+
+    #[test]
+    fn max_stable_version_aged() {
+        let published = |num, days_ago| Version {
+            created_at: Some(*DONT_CARE_DATETIME - Duration::days(days_ago)),
+            ..version(num, false)
+        };
+        let versions = Versions::from(vec![
+            published("1.0.0", 30),
+            published("1.1.0", 3),
+            published("1.2.0", 1),
+            published("2.0.0-rc.1", 5),
+            Version {
+                created_at: None,
+                ..version("1.3.0", false)
+            },
+        ]);
+        let aged = |hours| {
+            versions.max_stable_version_aged_at(Duration::hours(hours), *DONT_CARE_DATETIME)
+        };
+        assert_eq!(aged(0).unwrap(), &"1.2.0");
+        assert_eq!(aged(48).unwrap(), &"1.1.0");
+        assert_eq!(aged(24 * 7).unwrap(), &"1.0.0");
+        assert!(aged(24 * 365).is_none());
+    }
+
+    #[test]
+    fn latest_info_try_from() {
+        let versions = versions(&[("1.0.0", false), ("1.1.0-rc.1", false), ("1.1.0", true)]);
+        let latest = LatestInfo::try_from(&versions).unwrap();
+        assert_eq!(latest.max_version, "1.1.0-rc.1");
+        assert_eq!(latest.max_stable_version.unwrap(), "1.0.0");
+
+        let target = InstallTarget::from(&version("1.1.0", true));
+        assert_eq!(target.version, "1.1.0");
+        assert!(target.yanked);
+
+        let yanked = Versions::from(vec![version("1.0.0", true)]);
+        assert!(LatestInfo::try_from(&yanked).is_err());
+    }
+
+    #[test]
+    fn is_newest_prerelease() {
+        let published = |num, day, yanked| Version {
+            created_at: Some(*DONT_CARE_DATETIME + Duration::days(day)),
+            ..version(num, yanked)
+        };
+        let versions = Versions::from(vec![
+            published("1.0.0", 0, false),
+            published("1.1.0-beta.1", 1, true),
+        ]);
+        assert!(versions.is_newest_prerelease());
+        let versions = Versions::from(vec![
+            published("2.0.0-rc.1", 0, false),
+            published("1.0.1", 1, false),
+        ]);
+        assert!(!versions.is_newest_prerelease());
+        assert!(!Versions::from(Vec::new()).is_newest_prerelease());
+    }
+
+    #[test]
+    fn parse_version_lenient() {
+        let parse = super::try_parse_version_lenient;
+        assert_eq!(parse("1.2.3"), Some(SemVer::new(1, 2, 3)));
+        assert_eq!(parse("v1.2.3"), Some(SemVer::new(1, 2, 3)));
+        assert_eq!(parse("01.02.003"), Some(SemVer::new(1, 2, 3)));
+        assert_eq!(
+            parse("v1.0.0-beta.1+build"),
+            SemVer::parse("1.0.0-beta.1+build").ok()
+        );
+        assert_eq!(parse("1.2"), None);
+        assert_eq!(parse("vv1.2.3"), None);
+        assert_eq!(parse("1.2.3a"), None);
+        assert_eq!(parse("1..3"), None);
+        assert_eq!(parse("release-1.2.3"), None);
     }
-    /// Gets a mutable list of versions that were found.
-    pub fn versions_mut(&mut self) -> &mut Vec<Version> {
-        &mut self.versions
+
+    #[test]
+    fn from_json_lenient() {
+        let json = r#"{
+            "versions": [
+                {"num": "v1.0.0", "yanked": false, "created_at": null},
+                {"num": "1.01.0", "yanked": false, "created_at": null}
+            ],
+            "crate": {"max_version": "v1.1.0"}
+        }"#;
+        assert!(Versions::from_json(json).is_err());
+        assert!(Versions::from_json_with(json, false).is_err());
+        let versions = Versions::from_json_with(json, true).unwrap();
+        assert_eq!(versions.max_unyanked_version().unwrap(), &"1.1.0");
+        assert_eq!(versions.oldest_unyanked_version().unwrap(), &"1.0.0");
     }
-    /// Takes ownership of `self` and returns owned versions list.
-    pub fn versions_owned(self) -> Vec<Version> {
-        self.versions
+
+    proptest::proptest! {
+        #[test]
+        fn parse_version_lenient_never_panics(version in "\\PC*") {
+            super::try_parse_version_lenient(&version);
+        }
+
+        #[test]
+        fn parse_version_lenient_accepts_strict_versions(
+            version in "(0|[1-9][0-9]{0,5})\\.(0|[1-9][0-9]{0,5})\\.(0|[1-9][0-9]{0,5})(-[a-z]{1,5})?(\\+[a-z0-9]{1,5})?",
+        ) {
+            let strict = SemVer::parse(&version).unwrap();
+            proptest::prop_assert_eq!(super::try_parse_version_lenient(&version), Some(strict.clone()));
+            let prefixed = format!("v{}", version);
+            proptest::prop_assert_eq!(super::try_parse_version_lenient(&prefixed), Some(strict));
+        }
+
+        #[test]
+        fn parse_version_lenient_strips_leading_zeros(
+            major in 0u64..1000,
+            minor in 0u64..1000,
+            patch in 0u64..1000,
+        ) {
+            let padded = format!("{:03}.{:04}.{:05}", major, minor, patch);
+            proptest::prop_assert_eq!(
+                super::try_parse_version_lenient(&padded),
+                Some(SemVer::new(major, minor, patch))
+            );
+        }
+
+        #[test]
+        fn parse_version_lenient_rejects_letters_in_core(
+            version in "v?[0-9]{1,3}\\.[0-9]{1,3}\\.[0-9]{0,3}[a-zA-Z][0-9a-z]{0,3}",
+        ) {
+            proptest::prop_assert_eq!(super::try_parse_version_lenient(&version), None);
+        }
     }
-}
 
-impl Version {
-    /// Gets the SemVer MAJOR version
-    pub fn major(&self) -> u64 {
-        self.version.major
+    #[test]
+    fn suggest_requirement() {
+        let suggest = |num, style| version(num, false).suggest_requirement(style);
+        assert_eq!(suggest("1.5.2", ReqStyle::Caret), "1.5.2");
+        assert_eq!(suggest("1.5.2", ReqStyle::Exact), "=1.5.2");
+        assert_eq!(suggest("1.5.2", ReqStyle::MajorMinor), "1.5");
+        assert_eq!(suggest("1.5.2+build.7", ReqStyle::Exact), "=1.5.2");
+        assert_eq!(suggest("2.0.0-rc.1", ReqStyle::MajorMinor), "2.0.0-rc.1");
+
+        for num in ["1.5.2", "0.3.1", "2.0.0-rc.1", "1.0.0+build"] {
+            for style in [ReqStyle::Caret, ReqStyle::Exact, ReqStyle::MajorMinor] {
+                let req = semver::VersionReq::parse(&suggest(num, style)).unwrap();
+                assert!(req.matches(&SemVer::parse(num).unwrap()));
+            }
+        }
     }
-    /// Gets the SemVer MINOR version
-    pub fn minor(&self) -> u64 {
-        self.version.minor
+
+    #[test]
+    fn newest_ignores_undated_versions() {
+        let undated = |num, yanked| Version {
+            created_at: None,
+            ..version(num, yanked)
+        };
+        let versions = Versions::from(vec![
+            version("1.0.0", false),
+            version("1.1.0", true),
+            undated("2.0.0", false),
+            undated("2.1.0", true),
+        ]);
+        assert_eq!(versions.newest_version().unwrap(), &"1.1.0");
+        assert_eq!(versions.newest_unyanked_version().unwrap(), &"1.0.0");
+        assert_eq!(versions.newest_yanked_version().unwrap(), &"1.1.0");
+        assert_eq!(versions.newest_stable_version().unwrap(), &"1.0.0");
+
+        let versions = Versions::from(vec![undated("1.0.0", false)]);
+        assert!(versions.newest_version().is_none());
+        assert!(versions.earliest_unyanked_version().is_none());
     }
-    /// Gets the SemVer PATCH version
-    pub fn patch(&self) -> u64 {
-        self.version.patch
+
+    #[test]
+    fn recommended_update() {
+        let current = SemVer::parse("1.0.0").unwrap();
+
+        let list = versions(&[("1.0.0", false), ("1.1.0", false), ("1.2.0", true)]);
+        let update = list.recommended_update(&current);
+        assert_eq!(update.recommend.unwrap(), &"1.1.0");
+        assert!(update.newer_yanked_exists);
+
+        let list = versions(&[("1.0.0", false), ("1.1.0", true), ("1.2.0", false)]);
+        let update = list.recommended_update(&current);
+        assert_eq!(update.recommend.unwrap(), &"1.2.0");
+        assert!(!update.newer_yanked_exists);
+
+        let list = versions(&[("1.0.0", false), ("1.1.0", true)]);
+        let update = list.recommended_update(&current);
+        assert!(update.recommend.is_none());
+        assert!(update.newer_yanked_exists);
     }
-}
 
-impl PartialEq<SemVer> for Version {
-    fn eq(&self, rhs: &SemVer) -> bool {
-        self.version.eq(&rhs)
+    #[test]
+    fn health() {
+        let list = versions(&[
+            ("1.0.0", false),
+            ("1.1.0", true),
+            ("1.2.0", false),
+            ("2.0.0", true),
+        ]);
+        let health = list.health_at(*DONT_CARE_DATETIME + Duration::days(10));
+        assert_eq!(health.total, 4);
+        assert_eq!(health.yanked_count, 2);
+        assert_eq!(health.latest.as_ref().unwrap(), &"1.2.0");
+        assert_eq!(health.days_since_last_release, Some(10));
+        assert_eq!(health.yank_ratio, 0.5);
+
+        let json = serde_json::to_value(&health).unwrap();
+        assert_eq!(json["latest"]["num"], "1.2.0");
+        assert_eq!(json["yanked_count"], 2);
+
+        let health = Versions::from(Vec::new()).health();
+        assert_eq!(health.total, 0);
+        assert!(health.latest.is_none());
+        assert!(health.days_since_last_release.is_none());
     }
-}
 
-impl PartialEq<str> for Version {
-    fn eq(&self, rhs: &str) -> bool {
-        match SemVer::parse(rhs) {
-            Ok(version) => self.eq(&version),
-            Err(_) => false,
+    #[test]
+    fn max_version_prefers_unyanked_duplicate() {
+        for list in [
+            versions(&[("1.0.0", false), ("1.1.0", false), ("1.1.0", true)]),
+            versions(&[("1.0.0", false), ("1.1.0", true), ("1.1.0", false)]),
+        ] {
+            assert!(!list.max_version().unwrap().yanked);
+            assert!(!list.max_minor_version(1).unwrap().yanked);
+            assert!(!list.max_patch(1, 1).unwrap().yanked);
+            assert_eq!(list.max_unyanked_version().unwrap(), &"1.1.0");
+            assert_eq!(list.max_yanked_version().unwrap(), &"1.1.0");
         }
     }
-}
 
-impl PartialEq<&str> for Version {
-    fn eq(&self, rhs: &&str) -> bool {
-        self.eq(rhs.to_owned())
+    #[test]
+    fn max_for() {
+        let list = versions(&[
+            ("1.0.0", false),
+            ("1.1.0-rc.1", false),
+            ("2.0.0-beta.1", false),
+            ("2.0.0-rc.1", false),
+            ("2.0.0-rc.2", true),
+            ("2.0.0-rc2", false),
+            ("3.0.0-alpha.1", false),
+        ]);
+        let rc = || Channel::Named(String::from("rc"));
+        assert_eq!(list.max_for(Some(2), rc()).unwrap(), &"2.0.0-rc.1");
+        assert_eq!(list.max_for(Some(1), rc()).unwrap(), &"1.1.0-rc.1");
+        assert_eq!(list.max_for(None, rc()).unwrap(), &"2.0.0-rc.1");
+        assert!(list.max_for(Some(3), rc()).is_none());
+        assert_eq!(list.max_for(Some(2), Channel::Any).unwrap(), &"2.0.0-rc2");
+        assert_eq!(list.max_for(Some(1), Channel::Stable).unwrap(), &"1.0.0");
+        assert_eq!(
+            list.max_for(None, Channel::Prerelease).unwrap(),
+            &"3.0.0-alpha.1"
+        );
+        assert_eq!(list.install_target(rc()).unwrap().version, "2.0.0-rc.1");
     }
-}
 
-impl PartialOrd<SemVer> for Version {
-    fn partial_cmp(&self, rhs: &SemVer) -> Option<Ordering> {
-        self.version.partial_cmp(rhs)
+    #[test]
+    fn ordinal_of() {
+        let published = |num, day, yanked| Version {
+            created_at: Some(*DONT_CARE_DATETIME + Duration::days(day)),
+            ..version(num, yanked)
+        };
+        let list = Versions::from(vec![
+            published("1.1.0", 2, false),
+            published("0.1.0", 0, true),
+            published("1.0.0", 1, false),
+            published("0.2.0", 1, false),
+            Version {
+                created_at: None,
+                ..version("0.0.1", false)
+            },
+        ]);
+        let ordinal = |num| list.ordinal_of(&SemVer::parse(num).unwrap());
+        assert_eq!(ordinal("0.1.0"), Some(1));
+        assert_eq!(ordinal("0.2.0"), Some(2));
+        assert_eq!(ordinal("1.0.0"), Some(3));
+        assert_eq!(ordinal("1.1.0"), Some(4));
+        assert_eq!(ordinal("0.0.1"), None);
+        assert_eq!(ordinal("9.9.9"), None);
     }
-}
 
-impl PartialOrd<str> for Version {
-    fn partial_cmp(&self, rhs: &str) -> Option<Ordering> {
-        match SemVer::parse(rhs) {
-            Ok(version) => self.partial_cmp(&version),
-            Err(_) => None,
-        }
+    #[test]
+    fn latest_with_url() {
+        let list = versions(&[("1.0.0", false), ("1.1.0", false), ("1.2.0", true)]);
+        let (latest, url) = list.latest_with_url("my-crate").unwrap();
+        assert_eq!(latest, &"1.1.0");
+        assert_eq!(url, "https://crates.io/crates/my-crate/1.1.0");
+        assert!(versions(&[("1.0.0", true)])
+            .latest_with_url("my-crate")
+            .is_none());
     }
-}
 
-impl PartialOrd<&str> for Version {
-    fn partial_cmp(&self, rhs: &&str) -> Option<Ordering> {
-        self.partial_cmp(rhs.to_owned())
+    #[test]
+    fn reconcile() {
+        let registry = versions(&[("1.0.0", false), ("1.1.0", true), ("1.2.0", false)]);
+        let mirror = versions(&[("1.0.0", false), ("1.1.0", false), ("1.3.0", false)]);
+        let report = registry.reconcile(&mirror);
+        assert_eq!(report.only_in_self, vec![version("1.2.0", false)]);
+        assert_eq!(report.only_in_other, vec![version("1.3.0", false)]);
+        assert_eq!(report.yank_mismatches.len(), 1);
+        assert_eq!(report.yank_mismatches[0], "1.1.0");
+        assert!(report.yank_mismatches[0].yanked);
+        assert!(!report.is_consistent());
+
+        assert!(registry.reconcile(&registry).is_consistent());
     }
-}
 
-impl Display for Version {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.version)?;
-        if self.yanked {
-            write!(f, " (yanked)")
-        } else {
-            Ok(())
+    #[test]
+    fn minor_and_patch_updates() {
+        let list = versions(&[
+            ("1.0.0", false),
+            ("1.0.1", false),
+            ("1.0.2", true),
+            ("1.1.0", false),
+            ("1.2.0-rc.1", false),
+            ("2.0.0", false),
+        ]);
+        for current in [
+            "0.9.0", "1.0.0", "1.0.1", "1.1.0", "1.2.0", "2.0.0", "3.0.0",
+        ] {
+            let parsed = SemVer::parse(current).unwrap();
+            let minor = list
+                .max_unyanked_minor_version_owned(parsed.major)
+                .filter(|max| max > current);
+            let patch = list
+                .max_unyanked_patch_owned(parsed.major, parsed.minor)
+                .filter(|max| max > current);
+            assert_eq!(
+                __private::minor_update(&list, current, true).unwrap(),
+                minor
+            );
+            assert_eq!(
+                __private::patch_update(&list, current, true).unwrap(),
+                patch
+            );
         }
+        assert_eq!(
+            __private::minor_update(&list, "1.0.1", true)
+                .unwrap()
+                .unwrap(),
+            "1.2.0-rc.1"
+        );
+        assert_eq!(
+            __private::minor_update(&list, "1.0.1", false)
+                .unwrap()
+                .unwrap(),
+            "1.1.0"
+        );
+        assert_eq!(
+            __private::patch_update(&list, "1.0.0", true)
+                .unwrap()
+                .unwrap(),
+            "1.0.1"
+        );
+        assert!(__private::patch_update(&list, "not a version", true).is_err());
     }
-}
 
-impl From<Version> for SemVer {
-    fn from(v: Version) -> SemVer {
-        v.version
+    #[test]
+    fn yank_reason() {
+        let json = r#"{"versions": [
+            {"num": "1.0.0", "yanked": true, "created_at": null, "yank_message": "CVE-2023-0001"},
+            {"num": "1.0.1", "yanked": true, "created_at": null, "yank_message": null},
+            {"num": "1.0.2", "yanked": false, "created_at": null}
+        ]}"#;
+        let list = Versions::from_json(json).unwrap();
+        let reasons: Vec<Option<&str>> = list.versions().iter().map(Version::yank_reason).collect();
+        assert_eq!(reasons, vec![Some("CVE-2023-0001"), None, None]);
     }
-}
 
-fn build_url(crate_name: &str) -> String {
-    format!(
-        "https://crates.io/api/v1/crates/{crate_name}",
-        crate_name = crate_name,
-    )
-}
+    #[test]
+    fn max_unyanked_version_except() {
+        let list = versions(&[("1.0.0", false), ("1.1.0", false), ("1.2.0", true)]);
+        let deny: HashSet<SemVer> = [SemVer::parse("1.1.0").unwrap()].into_iter().collect();
+        assert_eq!(list.max_unyanked_version_except(&deny).unwrap(), &"1.0.0");
+        assert_eq!(
+            list.max_unyanked_version_except(&HashSet::new()).unwrap(),
+            &"1.1.0"
+        );
+    }
 
-/// Check for version updates with asynchronous requests.
-#[cfg(feature = "async")]
-pub mod r#async;
+    #[test]
+    fn check_update() {
+        let list = versions(&[("1.0.0", false), ("1.1.0", false), ("1.2.0", true)]);
+        let check = |current| list.check_update(&SemVer::parse(current).unwrap());
+        assert_eq!(
+            check("1.0.0"),
+            UpdateCheck::UpdateAvailable(version("1.1.0", false))
+        );
+        assert_eq!(check("1.1.0"), UpdateCheck::UpToDate);
+        assert_eq!(check("1.1.1"), UpdateCheck::Ahead);
+        assert_eq!(Option::<Version>::from(check("1.0.0")).unwrap(), "1.1.0");
+        assert_eq!(Option::<Version>::from(check("1.1.1")), None);
+        assert_eq!(
+            versions(&[("1.0.0", true)]).check_update(&SemVer::new(0, 1, 0)),
+            UpdateCheck::UpToDate
+        );
+    }
 
-/// Check for version updates with blocking requests.
-#[cfg(feature = "blocking")]
-pub mod blocking;
+    #[test]
+    fn parse_timestamp_lenient() {
+        let expected = Utc.with_ymd_and_hms(2020, 1, 2, 3, 4, 5).unwrap();
+        for timestamp in [
+            "2020-01-02T03:04:05Z",
+            "2020-01-02T05:04:05+02:00",
+            "2020-01-02 03:04:05Z",
+            "2020-01-02 03:04:05+00:00",
+            "2020-01-02 03:04:05",
+            "2020-01-02T03:04:05",
+        ] {
+            assert_eq!(
+                super::parse_timestamp_lenient(timestamp),
+                Some(expected),
+                "{}",
+                timestamp
+            );
+        }
+        assert_eq!(super::parse_timestamp_lenient("last tuesday"), None);
+    }
 
-/// Gets the name of the crate as defined in *your* `Cargo.toml`.
-#[macro_export]
-macro_rules! crate_name {
-    () => {
-        env!("CARGO_PKG_NAME")
-    };
-}
+    #[test]
+    fn from_json_lenient_timestamps() {
+        let json = r#"{"versions": [
+            {"num": "1.0.0", "yanked": false, "created_at": "2020-01-02 03:04:05"},
+            {"num": "1.1.0", "yanked": false, "created_at": "last tuesday"}
+        ]}"#;
+        assert!(Versions::from_json(json).is_err());
+        let list = Versions::from_json_with(json, true).unwrap();
+        assert_eq!(
+            list.versions()[0].created_at,
+            Some(Utc.with_ymd_and_hms(2020, 1, 2, 3, 4, 5).unwrap())
+        );
+        assert_eq!(list.versions()[1].created_at, None);
+    }
 
-/// Gets the version of the crate as defined in *your* `Cargo.toml`.
-///
-/// Will be `&str`
-#[macro_export]
-macro_rules! crate_version {
-    () => {
-        env!("CARGO_PKG_VERSION")
-    };
-}
+    #[test]
+    fn default_version() {
+        let json = r#"{
+            "versions": [
+                {"num": "1.0.0", "yanked": false, "created_at": null},
+                {"num": "2.0.0-rc.1", "yanked": false, "created_at": null}
+            ],
+            "crate": {"default_version": "1.0.0"}
+        }"#;
+        let list = Versions::from_json(json).unwrap();
+        assert_eq!(list.default_version().unwrap(), &"1.0.0");
+        assert!(versions(&[("1.0.0", false)]).default_version().is_none());
+    }
 
-/// Gets the major version of the crate as defined in *your* `Cargo.toml`.
-///
-/// Will be `&str`
-#[macro_export]
-macro_rules! crate_major_version {
-    () => {
-        env!("CARGO_PKG_VERSION_MAJOR")
-    };
-}
+    #[test]
+    fn bumped_and_is_taken() {
+        let current = version("1.2.3-rc.1+build", false);
+        assert_eq!(current.bumped(BumpKind::Major), SemVer::new(2, 0, 0));
+        assert_eq!(current.bumped(BumpKind::Minor), SemVer::new(1, 3, 0));
+        assert_eq!(current.bumped(BumpKind::Patch), SemVer::new(1, 2, 4));
 
-/// Gets the minor version of the crate as defined in *your* `Cargo.toml`.
-///
-/// Will be `&str`
-#[macro_export]
-macro_rules! crate_minor_version {
-    () => {
-        env!("CARGO_PKG_VERSION_MINOR")
-    };
-}
+        let list = versions(&[("1.2.3", false), ("1.2.4", true)]);
+        let max = list.max_unyanked_version().unwrap();
+        assert!(list.is_taken(&max.bumped(BumpKind::Patch)));
+        assert!(!list.is_taken(&max.bumped(BumpKind::Minor)));
+    }
 
-/// Gets the patch version of the crate as defined in *your* `Cargo.toml`.
-///
-/// Will be `&str`
-#[macro_export]
-macro_rules! crate_patch {
-    () => {
-        env!("CARGO_PKG_VERSION_PATCH")
-    };
-}
+    #[test]
+    fn compare_all_against() {
+        let list = versions(&[("1.0.0", false), ("1.1.0", true), ("0.9.0", false)]);
+        assert_eq!(
+            list.compare_all_against("1.0.0").unwrap(),
+            vec![Ordering::Equal, Ordering::Greater, Ordering::Less]
+        );
+        for (v, ordering) in list
+            .versions()
+            .iter()
+            .zip(list.compare_all_against("1.0.0").unwrap())
+        {
+            assert_eq!(v.partial_cmp(&"1.0.0"), Some(ordering));
+        }
+        assert!(list.compare_all_against("not a version").is_err());
+    }
 
-/// Defines an appropriate user agent for making requests.
-///
-/// `"<your-crate-name>/<version>"`
-#[macro_export]
-macro_rules! user_agent {
-    () => {
-        concat!($crate::crate_name!(), "/", $crate::crate_version!())
-    };
-}
+    #[test]
+    fn max_vs_newest() {
+        let mut list = versions(&[
+            ("1.0.0", false),
+            ("2.0.0", false),
+            ("1.0.1", false),
+            ("1.0.2", true),
+        ]);
+        for (days, version) in list.versions.iter_mut().enumerate() {
+            version.created_at = Some(*DONT_CARE_DATETIME + Duration::days(days as i64));
+        }
+        let (max, newest) = list.max_vs_newest();
+        assert_eq!(max.unwrap(), &"2.0.0");
+        assert_eq!(newest.unwrap(), &"1.0.1");
 
-#[cfg(not(any(feature = "async", feature = "blocking")))]
-compile_error!(
-    "\
-`check-latest` is almost completely useless without either `async` or \
-`blocking` enabled"
-);
+        let empty = versions(&[]);
+        let (max, newest) = empty.max_vs_newest();
+        assert!(max.is_none());
+        assert!(newest.is_none());
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::NaiveDateTime;
-    use lazy_static::lazy_static;
+    #[test]
+    fn recommended() {
+        let list = versions(&[
+            ("1.0.0", false),
+            ("1.1.0", false),
+            ("1.2.0", true),
+            ("2.0.0-rc.1", false),
+        ]);
+        assert_eq!(list.recommended().unwrap(), &"1.1.0");
+        assert_eq!(list.max_unyanked_version().unwrap(), &"2.0.0-rc.1");
 
-    lazy_static! {
-        static ref DONT_CARE_DATETIME: DateTime<Utc> = {
-            let naive = NaiveDateTime::from_timestamp(0, 0);
-            DateTime::from_utc(naive, Utc)
-        };
+        let list = versions(&[("1.0.0", true), ("2.0.0-rc.1", false)]);
+        assert!(list.recommended().is_none());
     }
 
     #[test]
-    fn is_greater_semver() {
-        let version = Version {
-            version: SemVer::parse("1.2.3").unwrap(),
-            yanked: false,
-            created_at: DONT_CARE_DATETIME.clone(),
-        };
-        let semver = SemVer::parse("1.2.0").unwrap();
-        assert!(version > semver);
+    fn max_unyanked_minor_for() {
+        let list = versions(&[
+            ("1.0.0", false),
+            ("1.2.0", false),
+            ("1.3.0", true),
+            ("2.0.0", false),
+        ]);
+        let current = SemVer::parse("1.1.0").unwrap();
+        assert_eq!(list.max_unyanked_minor_for(&current).unwrap(), &"1.2.0");
+        let current = SemVer::parse("1.2.0").unwrap();
+        assert!(list.max_unyanked_minor_for(&current).is_none());
     }
 
     #[test]
-    fn is_lesser_semver() {
-        let version = Version {
-            version: SemVer::parse("1.2.3").unwrap(),
-            yanked: false,
-            created_at: DONT_CARE_DATETIME.clone(),
-        };
-        let semver = SemVer::parse("1.3.0").unwrap();
-        assert!(version < semver);
+    fn from_reader() {
+        let body = r#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+        let list = Versions::from_reader(body.as_bytes()).unwrap();
+        assert_eq!(list.max_version().unwrap(), &"1.0.0");
+        assert!(Versions::from_reader("{".as_bytes()).is_err());
     }
 
     #[test]
-    fn is_greater_str() {
-        let version = Version {
-            version: SemVer::parse("1.2.3").unwrap(),
-            yanked: false,
-            created_at: DONT_CARE_DATETIME.clone(),
+    fn changelog_url() {
+        let with_crate = |meta: &str| {
+            let json = format!(r#"{{"versions": [], "crate": {}}}"#, meta);
+            Versions::from_json(&json).unwrap().changelog_url()
         };
-        assert!(version > "1.2.0");
+        assert_eq!(
+            with_crate(r#"{"repository": "https://github.com/me/my-crate.git", "homepage": "https://example.com"}"#).unwrap(),
+            "https://github.com/me/my-crate/blob/HEAD/CHANGELOG.md"
+        );
+        assert_eq!(
+            with_crate(r#"{"repository": "https://gitlab.com/me/my-crate/"}"#).unwrap(),
+            "https://gitlab.com/me/my-crate/-/blob/HEAD/CHANGELOG.md"
+        );
+        assert_eq!(
+            with_crate(r#"{"repository": "https://git.example.com/my-crate", "homepage": "https://example.com"}"#).unwrap(),
+            "https://example.com"
+        );
+        assert_eq!(
+            with_crate(r#"{"repository": "https://git.example.com/my-crate"}"#).unwrap(),
+            "https://git.example.com/my-crate"
+        );
+        assert_eq!(
+            with_crate(r#"{"documentation": "https://docs.rs/my-crate"}"#).unwrap(),
+            "https://docs.rs/my-crate"
+        );
+        assert!(with_crate("{}").is_none());
+        assert!(versions(&[]).changelog_url().is_none());
     }
 
     #[test]
-    fn is_lesser_str() {
-        let version = Version {
-            version: SemVer::parse("1.2.3").unwrap(),
-            yanked: false,
-            created_at: DONT_CARE_DATETIME.clone(),
+    fn group_by_major() {
+        let list = versions(&[
+            ("1.1.0", false),
+            ("2.0.0", true),
+            ("1.0.0", false),
+            ("0.1.0", false),
+            ("2.0.0-rc.1", false),
+        ]);
+        let groups = list.group_by_major();
+        assert_eq!(groups.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(groups[&1], vec![&"1.0.0", &"1.1.0"]);
+        assert_eq!(groups[&2], vec![&"2.0.0-rc.1", &"2.0.0"]);
+        assert!(groups[&2][1].yanked);
+    }
+
+    #[test]
+    fn best_upgrade_on_prerelease() {
+        let list = versions(&[
+            ("1.0.0-rc.1", false),
+            ("1.0.0", false),
+            ("1.1.0-rc.1", false),
+            ("1.1.0-rc.2", true),
+        ]);
+        let current = SemVer::parse("1.0.0-rc.1").unwrap();
+        assert_eq!(list.best_upgrade(&current).unwrap(), &"1.1.0-rc.1");
+        let current = SemVer::parse("1.1.0-rc.1").unwrap();
+        assert!(list.best_upgrade(&current).is_none());
+    }
+
+    #[test]
+    fn best_upgrade_on_stable() {
+        let list = versions(&[
+            ("1.0.0", false),
+            ("1.0.1", false),
+            ("1.0.2", true),
+            ("1.1.0-rc.1", false),
+        ]);
+        let current = SemVer::parse("1.0.0").unwrap();
+        assert_eq!(list.best_upgrade(&current).unwrap(), &"1.0.1");
+        let current = SemVer::parse("1.0.1").unwrap();
+        assert!(list.best_upgrade(&current).is_none());
+    }
+
+    #[test]
+    fn max_version_prefers_later_duplicate() {
+        let dated = |yanked, days| Version {
+            created_at: Some(*DONT_CARE_DATETIME + Duration::days(days)),
+            ..version("1.1.0", yanked)
         };
-        assert!(version < "1.3.0");
+        let undated = Version {
+            created_at: None,
+            ..version("1.1.0", false)
+        };
+        for list in [
+            Versions::from(vec![
+                dated(false, 2),
+                dated(false, 1),
+                undated.clone(),
+                dated(true, 3),
+            ]),
+            Versions::from(vec![
+                dated(true, 3),
+                undated.clone(),
+                dated(false, 1),
+                dated(false, 2),
+            ]),
+        ] {
+            let expected = Some(*DONT_CARE_DATETIME + Duration::days(2));
+            assert_eq!(list.max_version().unwrap().created_at, expected);
+            assert_eq!(list.max_unyanked_version().unwrap().created_at, expected);
+            assert_eq!(list.max_stable_version().unwrap().created_at, expected);
+        }
+    }
+
+    #[test]
+    fn newest_matching_req() {
+        let mut list = versions(&[
+            ("1.2.0", false),
+            ("2.0.0", false),
+            ("1.2.1", true),
+            ("1.1.0", false),
+        ]);
+        for (days, version) in list.versions.iter_mut().enumerate() {
+            version.created_at = Some(*DONT_CARE_DATETIME + Duration::days(days as i64));
+        }
+        let req = semver::VersionReq::parse("^1.1").unwrap();
+        assert_eq!(list.newest_matching_req(&req, false).unwrap(), &"1.1.0");
+        assert_eq!(list.newest_matching_req(&req, true).unwrap(), &"1.1.0");
+        let req = semver::VersionReq::parse("^1.2").unwrap();
+        assert_eq!(list.newest_matching_req(&req, false).unwrap(), &"1.2.0");
+        assert_eq!(list.newest_matching_req(&req, true).unwrap(), &"1.2.1");
+        let req = semver::VersionReq::parse("^3").unwrap();
+        assert!(list.newest_matching_req(&req, true).is_none());
+    }
+
+    #[test]
+    fn display_verbose() {
+        assert_eq!(
+            version("1.0.0", false).display_verbose().to_string(),
+            "1.0.0"
+        );
+        assert_eq!(
+            version("1.0.0", true).display_verbose().to_string(),
+            "1.0.0 (yanked)"
+        );
+        assert_eq!(
+            version("1.0.0-rc.1", false).display_verbose().to_string(),
+            "1.0.0-rc.1 (pre-release)"
+        );
+        assert_eq!(
+            version("1.0.0-rc.1", true).display_verbose().to_string(),
+            "1.0.0-rc.1 (pre-release) (yanked)"
+        );
+        assert_eq!(
+            version("1.0.0-rc.1", true).to_string(),
+            "1.0.0-rc.1 (yanked)"
+        );
+    }
+
+    #[test]
+    fn days_since_latest_release() {
+        let mut list = versions(&[("1.0.0", false), ("1.1.0", true)]);
+        for (days, version) in list.versions.iter_mut().enumerate() {
+            version.created_at = Some(*DONT_CARE_DATETIME + Duration::days(days as i64));
+        }
+        let now = *DONT_CARE_DATETIME + Duration::days(400) + Duration::hours(12);
+        assert_eq!(list.days_since_latest_release_at(now), Some(399));
+        assert!(versions(&[]).days_since_latest_release().is_none());
+    }
+
+    #[test]
+    fn any_newer() {
+        let list = versions(&[("1.0.0", false), ("1.1.0", false), ("1.2.0", true)]);
+        assert!(list.any_newer(&SemVer::new(1, 0, 0)));
+        assert!(!list.any_newer(&SemVer::new(1, 1, 0)));
+        assert!(list.any_newer_str("1.0.5").unwrap());
+        assert!(!list.any_newer_str("1.1.0").unwrap());
+        assert!(list.any_newer_str("v1.1.0").is_err());
+    }
+
+    #[test]
+    fn partition_yanked() {
+        let list = versions(&[
+            ("1.1.0", false),
+            ("1.2.0", true),
+            ("1.0.0", false),
+            ("0.1.0", true),
+        ]);
+        let (unyanked, yanked) = list.partition_yanked();
+        assert_eq!(unyanked, vec![&"1.0.0", &"1.1.0"]);
+        assert_eq!(yanked, vec![&"0.1.0", &"1.2.0"]);
+        assert!(yanked.iter().all(|v| v.yanked));
+    }
+
+    #[test]
+    fn max_update() {
+        let list = versions(&[
+            ("1.0.0", false),
+            ("1.1.0", false),
+            ("2.0.0-rc.1", false),
+            ("2.0.0", true),
+        ]);
+        assert_eq!(
+            __private::max_update(&list, "1.0.0", true)
+                .unwrap()
+                .unwrap(),
+            "2.0.0-rc.1"
+        );
+        assert_eq!(
+            __private::max_update(&list, "1.0.0", false)
+                .unwrap()
+                .unwrap(),
+            "1.1.0"
+        );
+        assert!(__private::max_update(&list, "1.1.0", false)
+            .unwrap()
+            .is_none());
+        assert!(__private::max_update(&list, "not a version", false).is_err());
+    }
+
+    #[test]
+    fn max_unyanked_version_is_cleared_by_versions_mut() {
+        let mut list = versions(&[("1.0.0", false), ("1.1.0", false)]);
+        assert_eq!(list.max_unyanked_version().unwrap(), &"1.1.0");
+        list.versions_mut().push(version("2.0.0", false));
+        assert_eq!(list.max_unyanked_version().unwrap(), &"2.0.0");
+        list.versions_mut().clear();
+        assert!(list.max_unyanked_version().is_none());
+    }
+
+    #[test]
+    fn in_req() {
+        let mut list = versions(&[
+            ("1.3.0", false),
+            ("1.4.0", true),
+            ("1.2.5", false),
+            ("2.0.0", false),
+        ]);
+        for (days, version) in list.versions.iter_mut().enumerate() {
+            version.created_at = Some(*DONT_CARE_DATETIME + Duration::days(days as i64));
+        }
+        let req = semver::VersionReq::parse("^1.2").unwrap();
+        assert_eq!(list.max_version_in_req(&req).unwrap(), &"1.4.0");
+        assert_eq!(list.max_unyanked_version_in_req(&req).unwrap(), &"1.3.0");
+        assert_eq!(list.newest_version_in_req(&req).unwrap(), &"1.2.5");
+        assert_eq!(list.newest_unyanked_version_in_req(&req).unwrap(), &"1.2.5");
+        let req = semver::VersionReq::parse(">=1.3, <2").unwrap();
+        assert_eq!(list.newest_version_in_req(&req).unwrap(), &"1.4.0");
+        assert_eq!(list.newest_unyanked_version_in_req(&req).unwrap(), &"1.3.0");
+    }
+
+    #[test]
+    fn recently_yanked() {
+        let mut list = versions(&[
+            ("1.0.0", true),
+            ("1.1.0", true),
+            ("1.2.0", false),
+            ("1.3.0", true),
+        ]);
+        for (days, version) in list.versions.iter_mut().enumerate() {
+            version.created_at = Some(*DONT_CARE_DATETIME + Duration::days(days as i64 * 10));
+        }
+        list.versions.push(Version {
+            created_at: None,
+            ..version("2.0.0", true)
+        });
+        let now = *DONT_CARE_DATETIME + Duration::days(35);
+        let yanked = list.recently_yanked_at(Duration::days(30), now);
+        assert_eq!(yanked, vec![&"1.3.0", &"1.1.0"]);
+        assert!(list.recently_yanked_at(Duration::days(1), now).is_empty());
+    }
+
+    #[test]
+    fn iter_unyanked_and_stable() {
+        let list = versions(&[
+            ("1.0.0", false),
+            ("1.1.0", true),
+            ("2.0.0-rc.1", false),
+            ("1.2.0", false),
+        ]);
+        let unyanked: Vec<&Version> = list.iter_unyanked().collect();
+        assert_eq!(unyanked, vec![&"1.0.0", &"2.0.0-rc.1", &"1.2.0"]);
+        let stable: Vec<&Version> = list.iter_stable().collect();
+        assert_eq!(stable, vec![&"1.0.0", &"1.2.0"]);
+    }
+
+    #[test]
+    fn total_downloads_survives_changes() {
+        let json = r#"{
+            "versions": [{"num": "1.0.0", "yanked": false, "created_at": null}],
+            "crate": {"max_version": "1.0.0", "downloads": 1234}
+        }"#;
+        let mut list = Versions::from_json(json).unwrap();
+        assert_eq!(list.filter(|_| true).total_downloads(), Some(1234));
+        list.versions_mut().push(version("2.0.0", false));
+        assert_eq!(list.total_downloads(), Some(1234));
+        assert_eq!(list.max_unyanked_version().unwrap(), &"2.0.0");
+    }
+
+    #[test]
+    fn changelog_url_survives_changes() {
+        let json = r#"{
+            "versions": [{"num": "1.0.0", "yanked": false, "created_at": null}],
+            "crate": {"repository": "https://github.com/me/my-crate"}
+        }"#;
+        let expected = "https://github.com/me/my-crate/blob/HEAD/CHANGELOG.md";
+        let mut list = Versions::from_json(json).unwrap();
+        assert_eq!(list.filter(|_| false).changelog_url().unwrap(), expected);
+        list.versions_mut().clear();
+        assert_eq!(list.changelog_url().unwrap(), expected);
+    }
+
+    #[test]
+    fn default_version_survives_changes() {
+        let json = r#"{
+            "versions": [
+                {"num": "1.0.0", "yanked": false, "created_at": null},
+                {"num": "2.0.0-rc.1", "yanked": false, "created_at": null}
+            ],
+            "crate": {"default_version": "1.0.0"}
+        }"#;
+        let mut list = Versions::from_json(json).unwrap();
+        assert_eq!(list.filter(|_| true).default_version().unwrap(), &"1.0.0");
+        assert!(list
+            .filter(|v| v.is_prerelease())
+            .default_version()
+            .is_none());
+        list.versions_mut().push(version("1.1.0", false));
+        assert_eq!(list.default_version().unwrap(), &"1.0.0");
+        list.versions_mut().retain(|v| v.is_prerelease());
+        assert!(list.default_version().is_none());
+    }
+
+    #[test]
+    fn url() {
+        let list = versions(&[("1.0.0", false)]);
+        assert!(list.url().is_none());
+        assert_eq!(list.url(), list.source_url());
+    }
+
+    #[test]
+    fn crates_io_url() {
+        let version = version("1.2.3-rc.1", true);
+        assert_eq!(
+            version.crates_io_url("my-crate"),
+            "https://crates.io/crates/my-crate/1.2.3-rc.1"
+        );
+    }
+
+    #[test]
+    fn table_columns_are_aligned() {
+        let mut versions = versions(&[("1.10.0", false), ("1.2.3", true)]);
+        versions.versions[0].downloads = Some(1234);
+        let table = versions.to_table();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "VERSION  YANKED  PUBLISHED   DOWNLOADS");
+        assert_eq!(lines[1], "1.10.0   no      1970-01-01  1234");
+        assert_eq!(lines[2], "1.2.3    yes     1970-01-01  -");
     }
 }
@@ -0,0 +1,125 @@
+//! Enabled with the `test-util` feature
+//!
+//! Helpers for testing code that checks for versions, without making requests
+//! to [Crates.io].
+//!
+//! [Crates.io]: https://crates.io/
+
+use crate::Versions;
+use serde_json::json;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// A running server started with [`serve`].
+///
+/// The server is stopped when this is dropped.
+pub struct ServerHandle {
+    addr: SocketAddr,
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake up the server, which is waiting for a connection.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Starts a local HTTP server that responds to every request with `versions`,
+/// in the same format as the [Crates.io] API.
+///
+/// Returns the base URL of the server, to be used with
+/// `FetchConfig::base_url`, and a handle that stops the server when it is
+/// dropped.
+///
+/// # Panics
+///
+/// Panics if the server can't be started.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use check_latest::test_util::serve;
+/// use check_latest::{FetchConfig, Versions};
+///
+/// let body = r#"{"versions": [{"num": "1.0.0", "yanked": false, "created_at": null}]}"#;
+/// let (url, _server) = serve(&Versions::from_json(body).unwrap());
+/// let config = FetchConfig::new("my-cool-crate/1.0.0").base_url(&url);
+/// let versions = Versions::fetch("my-cool-crate", &config).unwrap();
+/// assert_eq!(versions.max_version().unwrap(), &"1.0.0");
+/// ```
+///
+/// [Crates.io]: https://crates.io/
+pub fn serve(versions: &Versions) -> (String, ServerHandle) {
+    let body = json!({ "versions": versions.versions() }).to_string();
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Couldn't start the server");
+    let addr = listener
+        .local_addr()
+        .expect("Couldn't get the server's address");
+    let stopped = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let stopped = Arc::clone(&stopped);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let _ = respond(stream, &body);
+                }
+            }
+        })
+    };
+    let handle = ServerHandle {
+        addr,
+        stopped,
+        thread: Some(thread),
+    };
+    (format!("http://{}", addr), handle)
+}
+
+/// Reads a request from `stream`, and responds with `body`.
+fn respond(mut stream: TcpStream, body: &str) -> std::io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body,
+    )
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::FetchConfig;
+
+    #[test]
+    fn serves_versions() {
+        let body = r#"{"versions": [
+            {"num": "1.0.0", "yanked": false, "created_at": "2020-01-01T00:00:00Z"},
+            {"num": "1.1.0", "yanked": true, "created_at": null}
+        ]}"#;
+        let (url, server) = serve(&Versions::from_json(body).unwrap());
+        let config = FetchConfig::new("my-crate/1.0.0").base_url(&url);
+        let versions = Versions::fetch("my-crate", &config).unwrap();
+        assert_eq!(versions.versions().len(), 2);
+        assert_eq!(versions.max_unyanked_version().unwrap(), &"1.0.0");
+        assert_eq!(versions.max_yanked_version().unwrap(), &"1.1.0");
+
+        drop(server);
+        assert!(Versions::fetch("my-crate", &config).is_err());
+    }
+}